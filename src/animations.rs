@@ -56,12 +56,12 @@ pub fn move_in_tween(
     width: &f32,
     height: &f32,
 ) -> Tween<Node> {
-    return Tween::new(
+    Tween::new(
         EaseFunction::QuarticOut,
         Duration::from_millis(ANIM_SCROLL_LEFT),
         UiPositionLens {
             start: UiRect {
-                left: Val::Px(-screen_width as f32 - OFFSET - width),
+                left: Val::Px(-screen_width - OFFSET - width),
                 top: Val::Px(screen_height / 2. - height / 2.),
                 right: Val::Auto,
                 bottom: Val::Auto,
@@ -73,7 +73,7 @@ pub fn move_in_tween(
                 bottom: Val::Auto,
             },
         },
-    );
+    )
 }
 pub fn move_out_tween(
     screen_width: &f32,
@@ -0,0 +1,48 @@
+// Relays online matches between pairs of players.
+//
+// swirly-relay [--port N]
+use std::net::TcpListener;
+use std::process::exit;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use bevy::log::tracing_subscriber;
+use summer_jam::online::protocol::DEFAULT_PORT;
+use summer_jam::online::server;
+
+fn fail(message: String) -> ! {
+    eprintln!("swirly-relay: {}", message);
+    exit(2);
+}
+
+fn main() {
+    let mut port = DEFAULT_PORT;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "--port" => {
+                let Some(value) = inline.or_else(|| args.next()) else {
+                    fail("--port needs a value".to_string());
+                };
+                port = value
+                    .parse()
+                    .unwrap_or_else(|_| fail(format!("--port expects a number, got {}", value)));
+            }
+            _ => fail(format!("unknown flag {}", flag)),
+        }
+    }
+
+    tracing_subscriber::fmt().with_target(false).init();
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .unwrap_or_else(|e| fail(format!("could not listen on port {}: {}", port, e)));
+    println!("swirly-relay listening on ws://0.0.0.0:{}", port);
+
+    // Runs until the process is killed
+    if let Err(e) = server::serve(listener, Arc::new(AtomicBool::new(false))) {
+        fail(e.to_string());
+    }
+}
@@ -0,0 +1,128 @@
+// Plays bot-vs-bot matches without a window and prints aggregate statistics.
+//
+// swirly-sim [--red BOT] [--blue BOT] [--matches N] [--best-of N] [--extended]
+//            [--rules quick|standard|marathon] [--seed N] [--format text|csv|json]
+use std::num::ParseIntError;
+use std::process::exit;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+use summer_jam::rng::GameRng;
+use summer_jam::rules::{MatchRules, Ruleset};
+use summer_jam::settings::BotDifficulty;
+use summer_jam::sim::{self, SimConfig, Stat};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("swirly-sim: {}", message);
+    exit(2);
+}
+
+fn number<T: FromStr<Err = ParseIntError>>(flag: &str, value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|e| fail(format!("{} {}: {}", flag, value, e)))
+}
+
+// Serializes as a JSON object, keeping the rows in report order
+struct Report(Vec<(String, Stat)>);
+
+impl Serialize for Report {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, stat)| (name, stat)))
+    }
+}
+
+fn main() {
+    let mut config = SimConfig {
+        red: BotDifficulty::Normal,
+        blue: BotDifficulty::Normal,
+        ruleset: Ruleset::standard(),
+        rules: MatchRules::standard(),
+        rounds_to_win: 1,
+        matches: 1000,
+    };
+    let mut seed = None;
+    let mut format = Format::Text;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        // Accept both `--flag value` and `--flag=value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        if flag == "--extended" {
+            config.ruleset = Ruleset::extended();
+            continue;
+        }
+        let Some(value) = inline.or_else(|| args.next()) else {
+            fail(format!("{} needs a value", flag));
+        };
+        match flag.as_str() {
+            "--red" => {
+                config.red = sim::parse_difficulty(&value)
+                    .unwrap_or_else(|| fail(format!("unknown bot {}", value)))
+            }
+            "--blue" => {
+                config.blue = sim::parse_difficulty(&value)
+                    .unwrap_or_else(|| fail(format!("unknown bot {}", value)))
+            }
+            "--matches" => config.matches = number(&flag, &value),
+            "--best-of" => {
+                let best_of: u32 = number(&flag, &value);
+                if best_of.is_multiple_of(2) {
+                    fail(format!("--best-of expects an odd number, got {}", value));
+                }
+                config.rounds_to_win = best_of / 2 + 1;
+            }
+            "--seed" => seed = Some(number(&flag, &value)),
+            "--rules" => {
+                config.rules = match value.as_str() {
+                    "quick" => MatchRules::quick(),
+                    "standard" => MatchRules::standard(),
+                    "marathon" => MatchRules::marathon(),
+                    _ => fail(format!("unknown rules {}", value)),
+                }
+            }
+            "--format" => {
+                format = match value.as_str() {
+                    "text" => Format::Text,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => fail(format!("unknown format {}", value)),
+                }
+            }
+            _ => fail(format!("unknown argument {}", flag)),
+        }
+    }
+
+    let mut rng = seed.map(GameRng::new).unwrap_or_default();
+    let stats = sim::run(&config, &mut rng);
+    let report = stats.report(&config, rng.seed());
+
+    match format {
+        Format::Text => {
+            for (name, stat) in report {
+                println!("{:<28} {}", name, stat);
+            }
+        }
+        Format::Csv => {
+            println!("stat,value");
+            for (name, stat) in report {
+                println!("{},{}", name, stat);
+            }
+        }
+        Format::Json => match serde_json::to_string_pretty(&Report(report)) {
+            Ok(json) => println!("{}", json),
+            Err(e) => fail(format!("can't write JSON: {}", e)),
+        },
+    }
+}
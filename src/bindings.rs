@@ -0,0 +1,564 @@
+// Which keys and controller buttons pick each choice, saved between sessions.
+// Bindings are stored by name so the saved file doesn't depend on Bevy's types.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::combo::CHOICES;
+use crate::helper::key_label;
+use crate::types::Player;
+
+#[cfg(not(target_arch = "wasm32"))]
+const BINDINGS_DIR: &str = "config";
+#[cfg(not(target_arch = "wasm32"))]
+const BINDINGS_FILE: &str = "config/bindings.ron";
+#[cfg(target_arch = "wasm32")]
+const BINDINGS_KEY: &str = "summer_jam.bindings";
+
+// One slot per entry in combo::CHOICES, then one for locking in a pick
+pub const SLOTS: usize = 6;
+pub const CONFIRM: usize = 5;
+// Controller buttons per slot, so the face buttons and the d-pad can both pick
+pub const PAD_BUTTONS: usize = 2;
+
+// Keys that can be bound. Anything else, such as Escape, is left for the menus.
+const BINDABLE_KEYS: [KeyCode; 67] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Backslash,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+];
+
+const BINDABLE_BUTTONS: [GamepadButton; 12] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::West,
+    GamepadButton::North,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::LeftTrigger2,
+    GamepadButton::RightTrigger2,
+];
+
+// Short label for a controller button, using the usual Xbox names
+pub fn pad_label(button: GamepadButton) -> String {
+    match button {
+        GamepadButton::South => "Pad A".to_string(),
+        GamepadButton::East => "Pad B".to_string(),
+        GamepadButton::West => "Pad X".to_string(),
+        GamepadButton::North => "Pad Y".to_string(),
+        GamepadButton::DPadUp => "D-Pad Up".to_string(),
+        GamepadButton::DPadDown => "D-Pad Down".to_string(),
+        GamepadButton::DPadLeft => "D-Pad Left".to_string(),
+        GamepadButton::DPadRight => "D-Pad Right".to_string(),
+        GamepadButton::LeftTrigger => "LB".to_string(),
+        GamepadButton::RightTrigger => "RB".to_string(),
+        GamepadButton::LeftTrigger2 => "LT".to_string(),
+        GamepadButton::RightTrigger2 => "RT".to_string(),
+        _ => format!("{:?}", button),
+    }
+}
+
+// What a slot does, e.g. "Water" or "Lock In"
+pub fn slot_name(slot: usize) -> String {
+    CHOICES
+        .get(slot)
+        .map_or("Lock In".to_string(), |(element, _)| element.to_string())
+}
+
+pub fn bindable_key(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+pub fn bindable_button(button: GamepadButton) -> bool {
+    BINDABLE_BUTTONS.contains(&button)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    // What is printed on the key, which differs from the key's position on
+    // layouts such as AZERTY
+    pub label: String,
+}
+
+impl KeyBinding {
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            label: key_label(key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerBindings {
+    pub keys: [KeyBinding; SLOTS],
+    pub pad: [[GamepadButton; PAD_BUTTONS]; SLOTS],
+}
+
+impl PlayerBindings {
+    fn defaults(player: Player) -> Self {
+        let keys = match player {
+            Player::One => [
+                KeyCode::KeyA,
+                KeyCode::KeyS,
+                KeyCode::KeyD,
+                KeyCode::KeyF,
+                KeyCode::KeyG,
+                KeyCode::Space,
+            ],
+            Player::Two => [
+                KeyCode::KeyJ,
+                KeyCode::KeyK,
+                KeyCode::KeyL,
+                KeyCode::Semicolon,
+                KeyCode::Quote,
+                KeyCode::Enter,
+            ],
+        };
+        Self {
+            keys: keys.map(KeyBinding::new),
+            // Each player has their own controller, so both share the same layout.
+            // Face buttons first, then the matching side of the d-pad.
+            pad: [
+                [GamepadButton::South, GamepadButton::DPadDown],
+                [GamepadButton::East, GamepadButton::DPadRight],
+                [GamepadButton::West, GamepadButton::DPadLeft],
+                [GamepadButton::North, GamepadButton::DPadUp],
+                [GamepadButton::RightTrigger, GamepadButton::LeftTrigger],
+                [GamepadButton::RightTrigger2, GamepadButton::LeftTrigger2],
+            ],
+        }
+    }
+
+    pub fn key_slot(&self, key: KeyCode) -> Option<usize> {
+        self.keys.iter().position(|binding| binding.key == key)
+    }
+
+    // The slot and column a button is bound to
+    pub fn pad_slot(&self, button: GamepadButton) -> Option<(usize, usize)> {
+        self.pad.iter().enumerate().find_map(|(slot, buttons)| {
+            buttons
+                .iter()
+                .position(|other| *other == button)
+                .map(|column| (slot, column))
+        })
+    }
+}
+
+// Which choice wins while more than one is held down
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PressPriority {
+    #[default]
+    MostRecent,
+    FirstPressed,
+}
+
+impl PressPriority {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PressPriority::MostRecent => "Latest Press Wins",
+            PressPriority::FirstPressed => "First Press Wins",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            PressPriority::MostRecent => PressPriority::FirstPressed,
+            PressPriority::FirstPressed => PressPriority::MostRecent,
+        }
+    }
+}
+
+// Why a binding could not be changed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BindConflict {
+    // The key belongs to the other player, at this slot
+    OtherPlayer(Player, usize),
+    // The key or button is bound to two of the player's slots
+    SamePlayer(Player, usize),
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    pub player_one: PlayerBindings,
+    pub player_two: PlayerBindings,
+    pub priority: PressPriority,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            player_one: PlayerBindings::defaults(Player::One),
+            player_two: PlayerBindings::defaults(Player::Two),
+            priority: PressPriority::default(),
+        }
+    }
+}
+
+impl Bindings {
+    pub fn player(&self, player: Player) -> &PlayerBindings {
+        match player {
+            Player::One => &self.player_one,
+            Player::Two => &self.player_two,
+        }
+    }
+
+    fn player_mut(&mut self, player: Player) -> &mut PlayerBindings {
+        match player {
+            Player::One => &mut self.player_one,
+            Player::Two => &mut self.player_two,
+        }
+    }
+
+    // Both players share the keyboard, so a key can only belong to one of them.
+    // A key already used by the same player swaps with the slot being changed.
+    pub fn bind_key(
+        &mut self,
+        player: Player,
+        slot: usize,
+        binding: KeyBinding,
+    ) -> Result<(), BindConflict> {
+        self.check_key(player, binding.key)?;
+        let bindings = self.player_mut(player);
+        if let Some(other) = bindings.key_slot(binding.key) {
+            bindings.keys.swap(slot, other);
+        } else {
+            bindings.keys[slot] = binding;
+        }
+        Ok(())
+    }
+
+    fn check_key(&self, player: Player, key: KeyCode) -> Result<(), BindConflict> {
+        let opponent = player.opponent();
+        match self.player(opponent).key_slot(key) {
+            Some(other) => Err(BindConflict::OtherPlayer(opponent, other)),
+            None => Ok(()),
+        }
+    }
+
+    // Controllers aren't shared, so only the player's own buttons can clash
+    pub fn bind_button(
+        &mut self,
+        player: Player,
+        slot: usize,
+        column: usize,
+        button: GamepadButton,
+    ) {
+        let bindings = self.player_mut(player);
+        if let Some((other_slot, other_column)) = bindings.pad_slot(button) {
+            bindings.pad[other_slot][other_column] = bindings.pad[slot][column];
+        }
+        bindings.pad[slot][column] = button;
+    }
+
+    // Saved files can be edited by hand, so check them like a rebind would
+    fn check(&self) -> Result<(), BindConflict> {
+        for player in [Player::One, Player::Two] {
+            let bindings = self.player(player);
+            for (slot, binding) in bindings.keys.iter().enumerate() {
+                self.check_key(player, binding.key)?;
+                if bindings.key_slot(binding.key) != Some(slot) {
+                    return Err(BindConflict::SamePlayer(player, slot));
+                }
+            }
+            for (slot, buttons) in bindings.pad.iter().enumerate() {
+                for (column, button) in buttons.iter().enumerate() {
+                    if bindings.pad_slot(*button) != Some((slot, column)) {
+                        return Err(BindConflict::SamePlayer(player, slot));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Reads the saved bindings, keeping the default for anything missing or unknown,
+    // and every default if the saved bindings clash
+    pub fn load() -> Self {
+        let Some(contents) = read_saved() else {
+            return Self::default();
+        };
+        match ron::from_str::<SavedBindings>(&contents) {
+            Ok(saved) => saved.bindings().unwrap_or_else(|conflict| {
+                warn!("Saved bindings clash ({:?}), using the defaults", conflict);
+                Self::default()
+            }),
+            Err(e) => {
+                warn!("Could not read saved bindings: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let saved = SavedBindings {
+            player_one: SavedPlayer::from(&self.player_one),
+            player_two: SavedPlayer::from(&self.player_two),
+            priority: self.priority,
+        };
+        match ron::ser::to_string_pretty(&saved, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => write_saved(&contents),
+            Err(e) => warn!("Could not serialise bindings: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBindings {
+    player_one: SavedPlayer,
+    player_two: SavedPlayer,
+    #[serde(default)]
+    priority: PressPriority,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedPlayer {
+    // Key names and their labels, e.g. ("KeyQ", "A")
+    keys: Vec<(String, String)>,
+    pad: Vec<String>,
+    // Added later, so older files fall back to the default d-pad buttons
+    #[serde(default)]
+    pad_alt: Vec<String>,
+}
+
+impl From<&PlayerBindings> for SavedPlayer {
+    fn from(bindings: &PlayerBindings) -> Self {
+        Self {
+            keys: bindings
+                .keys
+                .iter()
+                .map(|binding| (format!("{:?}", binding.key), binding.label.clone()))
+                .collect(),
+            pad: bindings
+                .pad
+                .iter()
+                .map(|buttons| format!("{:?}", buttons[0]))
+                .collect(),
+            pad_alt: bindings
+                .pad
+                .iter()
+                .map(|buttons| format!("{:?}", buttons[1]))
+                .collect(),
+        }
+    }
+}
+
+impl SavedPlayer {
+    fn apply(&self, bindings: &mut PlayerBindings) {
+        for (slot, (name, label)) in self.keys.iter().take(SLOTS).enumerate() {
+            match BINDABLE_KEYS
+                .iter()
+                .find(|key| format!("{:?}", key) == *name)
+            {
+                Some(&key) => {
+                    bindings.keys[slot] = KeyBinding {
+                        key,
+                        label: label.clone(),
+                    }
+                }
+                None => warn!("Unknown key {} in saved bindings", name),
+            }
+        }
+        for (column, names) in [&self.pad, &self.pad_alt].into_iter().enumerate() {
+            for (slot, name) in names.iter().take(SLOTS).enumerate() {
+                match BINDABLE_BUTTONS
+                    .iter()
+                    .find(|button| format!("{:?}", button) == *name)
+                {
+                    Some(&button) => bindings.pad[slot][column] = button,
+                    None => warn!("Unknown button {} in saved bindings", name),
+                }
+            }
+        }
+    }
+}
+
+impl SavedBindings {
+    fn bindings(&self) -> Result<Bindings, BindConflict> {
+        let mut bindings = Bindings::default();
+        self.player_one.apply(&mut bindings.player_one);
+        self.player_two.apply(&mut bindings.player_two);
+        bindings.priority = self.priority;
+        bindings.check()?;
+        Ok(bindings)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved() -> Option<String> {
+    std::fs::read_to_string(BINDINGS_FILE).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_saved(contents: &str) {
+    if let Err(e) =
+        std::fs::create_dir_all(BINDINGS_DIR).and_then(|_| std::fs::write(BINDINGS_FILE, contents))
+    {
+        warn!("Could not write {}: {}", BINDINGS_FILE, e);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved() -> Option<String> {
+    local_storage()?.get_item(BINDINGS_KEY).ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_saved(contents: &str) {
+    let saved = local_storage().map(|storage| storage.set_item(BINDINGS_KEY, contents));
+    if !matches!(saved, Some(Ok(()))) {
+        warn!("Could not save bindings to local storage");
+    }
+}
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Bindings::load());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(player_one: &str, player_two: &str) -> SavedBindings {
+        let contents = format!(
+            "(player_one: (keys: [{}], pad: []), player_two: (keys: [{}], pad: []))",
+            player_one, player_two
+        );
+        ron::from_str(&contents).unwrap()
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert_eq!(Bindings::default().check(), Ok(()));
+    }
+
+    #[test]
+    fn defaults_include_the_d_pad() {
+        let bindings = Bindings::default();
+        assert_eq!(
+            bindings.player_one.pad_slot(GamepadButton::DPadDown),
+            Some((0, 1))
+        );
+        assert_eq!(
+            bindings.player_two.pad_slot(GamepadButton::LeftTrigger),
+            Some((4, 1))
+        );
+    }
+
+    #[test]
+    fn older_files_keep_the_default_d_pad() {
+        let bindings = saved(r#"("KeyQ", "Q")"#, "").bindings().unwrap();
+        assert_eq!(bindings.player_one.keys[0].key, KeyCode::KeyQ);
+        assert_eq!(bindings.player_one.pad, Bindings::default().player_one.pad);
+    }
+
+    #[test]
+    fn loading_rejects_a_shared_key() {
+        // J is Blue's first key by default
+        assert_eq!(
+            saved(r#"("KeyJ", "J")"#, "").bindings().map(|_| ()),
+            Err(BindConflict::OtherPlayer(Player::Two, 0))
+        );
+    }
+
+    #[test]
+    fn loading_rejects_a_repeated_key() {
+        // S is Red's second key by default
+        assert_eq!(
+            saved(r#"("KeyS", "S")"#, "").bindings().map(|_| ()),
+            Err(BindConflict::SamePlayer(Player::One, 1))
+        );
+    }
+
+    #[test]
+    fn binding_a_used_button_swaps() {
+        let mut bindings = Bindings::default();
+        bindings.bind_button(Player::One, 0, 0, GamepadButton::DPadUp);
+        assert_eq!(
+            bindings.player_one.pad[0],
+            [GamepadButton::DPadUp, GamepadButton::DPadDown]
+        );
+        assert_eq!(
+            bindings.player_one.pad[3],
+            [GamepadButton::North, GamepadButton::South]
+        );
+        assert_eq!(bindings.check(), Ok(()));
+    }
+}
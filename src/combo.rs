@@ -1,19 +1,31 @@
 use crate::animations::shake_player_sequence;
-use crate::config::{FADED_PLAYER, MAX_HEALTH, START_STATE};
+use crate::bindings::{Bindings, PlayerBindings, PressPriority, CONFIRM, PAD_BUTTONS};
+use crate::config::{FADED_PLAYER, START_STATE};
 use crate::events::{SelectActionEvent, SelectElementEvent};
 use crate::globals::{GameAssets, PlayerAsset};
 use crate::helper::despawn;
+use crate::rules::{ChoiceSelection, MatchRules, MatchState, ResolveResult, TurnReport};
+use crate::ruleset::{RulesetAsset, RulesetHandles};
 use crate::schedule::GameSet;
-use crate::settings::{GameMode, GameSettings};
+use crate::settings::{GameSettings, GameVariant};
 use crate::state::GameState;
-use crate::types::{Action, Choice, Element, Outcome, Player};
+use crate::types::{Action, Choice, Element, Player};
 use bevy::prelude::*;
 use bevy_tweening::Animator;
-use std::collections::HashMap;
 use std::time::Duration;
 
 const PLAYER_LENGTH: f32 = 1.6;
 
+// Each binding selects an element along with the action it doubles.
+// Classic matches only use the first three.
+pub const CHOICES: [(Element, Action); 5] = [
+    (Element::Water, Action::Toilet),
+    (Element::Grass, Action::Underwear),
+    (Element::Fire, Action::Hand),
+    (Element::Metal, Action::Finger),
+    (Element::Earth, Action::Elbow),
+];
+
 #[derive(Component)]
 struct AnimationConfig {
     first_sprite_index: usize,
@@ -37,37 +49,127 @@ impl AnimationConfig {
     }
 }
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct ChoiceSelection {
-    pub element: Choice,
-    pub action: Choice,
-}
-
-impl ChoiceSelection {
-    pub fn can_double(&self) -> bool {
-        let action = Choice::get_complement(&self.action);
-        println!("Element {} Action {}", self.element, action);
-        self.element == Choice::get_complement(&self.action)
-    }
-}
-
 #[derive(Debug, Default)]
 pub struct PlayerInput {
-    pub map: HashMap<KeyCode, ChoiceSelection>,
+    // In the same order as CHOICES, so presses on the same frame always settle the same way
+    pub map: Vec<(KeyCode, ChoiceSelection)>,
+    pub pad: Vec<(GamepadButton, ChoiceSelection)>,
+    // Locks in the current pick
+    pub confirm: Option<(KeyCode, [GamepadButton; PAD_BUTTONS])>,
+    pub priority: PressPriority,
+    // Choices held down in the order they were pressed, with the time of each press
+    held: Vec<(ChoiceSelection, f64)>,
 }
 
 impl PlayerInput {
-    fn new(map: HashMap<KeyCode, ChoiceSelection>) -> Self {
-        Self { map }
+    fn new(bindings: &PlayerBindings, priority: PressPriority, variant: GameVariant) -> Self {
+        let keys: Vec<KeyCode> = bindings.keys.iter().map(|binding| binding.key).collect();
+        Self {
+            map: create_inputs(&keys, variant),
+            pad: (0..PAD_BUTTONS)
+                .flat_map(|column| {
+                    let buttons: Vec<GamepadButton> =
+                        bindings.pad.iter().map(|buttons| buttons[column]).collect();
+                    create_inputs(&buttons, variant)
+                })
+                .collect(),
+            confirm: Some((bindings.keys[CONFIRM].key, bindings.pad[CONFIRM])),
+            priority,
+            held: vec![],
+        }
+    }
+
+    // Records new presses and forgets released ones. A choice is held while either its
+    // key or its button is down.
+    pub fn update(&mut self, keys: &ButtonInput<KeyCode>, pad: Option<&Gamepad>, now: f64) {
+        let down = |choice: &ChoiceSelection| {
+            self.map
+                .iter()
+                .any(|(key, other)| other == choice && keys.pressed(*key))
+                || pad.is_some_and(|pad| {
+                    self.pad
+                        .iter()
+                        .any(|(button, other)| other == choice && pad.pressed(*button))
+                })
+        };
+        self.held.retain(|(choice, _)| down(choice));
+        for (_, choice) in &self.map {
+            if down(choice) && !self.held.iter().any(|(other, _)| other == choice) {
+                self.held.push((*choice, now));
+            }
+        }
+    }
+
+    // The held choice that wins under the player's priority, and when it was pressed.
+    // Choices pressed on the same frame go to the earliest in CHOICES either way.
+    pub fn held(&self) -> Option<(ChoiceSelection, f64)> {
+        let pressed_at = match self.priority {
+            PressPriority::MostRecent => self.held.last()?.1,
+            PressPriority::FirstPressed => self.held.first()?.1,
+        };
+        self.held
+            .iter()
+            .find(|(_, time)| *time == pressed_at)
+            .copied()
+    }
+
+    // The choice pressed down this frame, if any
+    pub fn just_pressed(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        pad: Option<&Gamepad>,
+    ) -> Option<ChoiceSelection> {
+        let key = self
+            .map
+            .iter()
+            .find(|(key, _)| keys.just_pressed(*key))
+            .map(|(_, choice)| *choice);
+        key.or_else(|| {
+            let pad = pad?;
+            self.pad
+                .iter()
+                .find(|(button, _)| pad.just_pressed(*button))
+                .map(|(_, choice)| *choice)
+        })
+    }
+
+    pub fn confirm_pressed(&self, keys: &ButtonInput<KeyCode>, pad: Option<&Gamepad>) -> bool {
+        self.confirm.is_some_and(|(key, buttons)| {
+            keys.just_pressed(key) || pad.is_some_and(|pad| pad.any_just_pressed(buttons))
+        })
     }
 }
 
-#[derive(Debug)]
+fn create_inputs<T: Copy>(keys: &[T], variant: GameVariant) -> Vec<(T, ChoiceSelection)> {
+    keys.iter()
+        .zip(CHOICES)
+        .take(variant.choice_count())
+        .map(|(key, (element, action))| {
+            (
+                *key,
+                ChoiceSelection {
+                    element: Choice::Element(element),
+                    action: Choice::Action(action),
+                },
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
 pub struct PlayerData {
     pub starting_pos: Vec3,
     pub choice_selection: ChoiceSelection,
-    pub health: i32,
     pub input: PlayerInput,
+    // Last picks made before the countdown ran out, for repeating on a timeout
+    pub last_selection: ChoiceSelection,
+    pub element_timed_out: bool,
+    pub action_timed_out: bool,
+    // Blind picks hold back all feedback until the reveal
+    pub blind: bool,
+    pub hidden_pick: bool,
+    // A locked in pick can't change until the next countdown
+    pub locked: bool,
 }
 
 impl PlayerData {
@@ -77,9 +179,13 @@ impl PlayerData {
         choice: Choice,
         writer: &mut EventWriter<SelectElementEvent>,
     ) {
-        if self.choice_selection.element != choice {
+        if !self.locked && self.choice_selection.element != choice {
             self.choice_selection.element = choice;
-            writer.send(SelectElementEvent::new(player, choice));
+            if self.blind {
+                self.hidden_pick = true;
+            } else {
+                writer.send(SelectElementEvent::new(player, choice));
+            }
         }
     }
     pub fn select_action(
@@ -88,20 +194,20 @@ impl PlayerData {
         choice: Choice,
         writer: &mut EventWriter<SelectActionEvent>,
     ) {
-        if self.choice_selection.action != choice {
+        if !self.locked && self.choice_selection.action != choice {
             self.choice_selection.action = choice;
-            writer.send(SelectActionEvent::new(player));
+            if self.blind {
+                self.hidden_pick = true;
+            } else {
+                writer.send(SelectActionEvent::new(player));
+            }
         }
     }
-}
 
-impl Default for PlayerData {
-    fn default() -> Self {
-        Self {
-            starting_pos: Vec3::default(),
-            choice_selection: ChoiceSelection::default(),
-            input: PlayerInput::default(),
-            health: MAX_HEALTH,
+    // Only a made pick can be locked in
+    pub fn lock(&mut self, pick: Choice) {
+        if pick != Choice::None {
+            self.locked = true;
         }
     }
 }
@@ -110,11 +216,14 @@ impl Default for PlayerData {
 pub struct GameData {
     pub player_one: PlayerData,
     pub player_two: PlayerData,
-    pub action: u32,
-    pub advantage: Player,
+    pub state: MatchState,
 }
 
 impl GameData {
+    pub fn both_locked(&self) -> bool {
+        self.player_one.locked && self.player_two.locked
+    }
+
     pub fn get_action(&self, player: Player) -> Choice {
         match player {
             Player::One => self.player_one.choice_selection.action,
@@ -125,75 +234,52 @@ impl GameData {
     pub fn reset(&mut self) {
         self.player_one = PlayerData::default();
         self.player_two = PlayerData::default();
-        self.action = 0;
+        self.state = MatchState::default();
     }
 
-    pub fn reset_action(&mut self) {
-        self.player_one.choice_selection.action = Choice::None;
-        self.player_two.choice_selection.action = Choice::None;
+    pub fn next_round(&mut self) {
+        self.player_one.choice_selection = ChoiceSelection::default();
+        self.player_two.choice_selection = ChoiceSelection::default();
+        self.state.next_round();
     }
 
-    pub fn get_action_result(&self) -> ResolveResult {
-        self.resolve(|player| player.choice_selection.action)
-    }
-
-    pub fn process_turn(&mut self) {
-        // Increment Action by One
-        self.action += 1;
-        // Update Healths
-        let result = self.get_action_result();
-        match result.outcome {
-            Outcome::PlayerOne => {
-                if self.player_one.choice_selection.can_double() {
-                    self.player_two.health -= 2;
-                } else {
-                    self.player_two.health -= 1;
-                }
-            }
-            Outcome::PlayerTwo => {
-                if self.player_two.choice_selection.can_double() {
-                    self.player_one.health -= 2;
-                } else {
-                    self.player_one.health -= 1;
-                }
-            }
-            Outcome::Draw => (),
+    pub fn player(&self, player: Player) -> &PlayerData {
+        match player {
+            Player::One => &self.player_one,
+            Player::Two => &self.player_two,
         }
-        // Reset Choices
-        self.reset_action();
     }
 
-    pub fn resolve(&self, get_choice: fn(&PlayerData) -> Choice) -> ResolveResult {
-        let choice_one = get_choice(&self.player_one);
-        let choice_two = get_choice(&self.player_two);
-        if choice_one > choice_two {
-            ResolveResult {
-                outcome: Outcome::PlayerOne,
-                choice: choice_one.clone(),
-            }
-        } else if choice_one < choice_two {
-            ResolveResult {
-                outcome: Outcome::PlayerTwo,
-                choice: choice_two.clone(),
-            }
-        } else {
-            ResolveResult {
-                outcome: Outcome::Draw,
-                choice: Choice::None,
-            }
+    pub fn player_mut(&mut self, player: Player) -> &mut PlayerData {
+        match player {
+            Player::One => &mut self.player_one,
+            Player::Two => &mut self.player_two,
         }
     }
 
-    pub fn can_end_game(&self) -> bool {
-        self.player_one.health <= 0 || self.player_two.health <= 0
+    pub fn reset_action(&mut self) {
+        for player_data in [&mut self.player_one, &mut self.player_two] {
+            player_data.choice_selection.action = Choice::None;
+            player_data.element_timed_out = false;
+            player_data.action_timed_out = false;
+        }
     }
 
-    pub fn get_winner(&self) -> Player {
-        if self.player_one.health > self.player_two.health {
-            Player::One
-        } else {
-            Player::Two
-        }
+    pub fn get_action_result(&self) -> ResolveResult {
+        self.state.ruleset.resolve(
+            self.player_one.choice_selection.action,
+            self.player_two.choice_selection.action,
+        )
+    }
+
+    pub fn process_turn(&mut self) -> TurnReport {
+        let report = self.state.step(
+            self.player_one.choice_selection,
+            self.player_two.choice_selection,
+        );
+        // Reset Choices
+        self.reset_action();
+        report
     }
 }
 
@@ -203,12 +289,6 @@ pub struct PlayerOne;
 #[derive(Component, Debug)]
 pub struct PlayerTwo;
 
-#[derive(Debug)]
-pub struct ResolveResult {
-    pub outcome: Outcome,
-    pub choice: Choice,
-}
-
 pub struct ComboPlugin;
 
 impl Plugin for ComboPlugin {
@@ -234,65 +314,37 @@ impl Plugin for ComboPlugin {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn setup_game(
     mut commands: Commands,
     settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+    bindings: Res<Bindings>,
     game_assets: Res<GameAssets>,
     mut game_data: ResMut<GameData>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    rulesets: Res<RulesetHandles>,
+    ruleset_assets: Res<Assets<RulesetAsset>>,
 ) {
     game_data.reset();
-    let mut player_one_inputs: HashMap<KeyCode, ChoiceSelection> = HashMap::new();
-    player_one_inputs.insert(
-        KeyCode::KeyA,
-        ChoiceSelection {
-            action: Choice::Action(Action::Toilet),
-            element: Choice::Element(Element::Water),
-        },
-    );
-    player_one_inputs.insert(
-        KeyCode::KeyS,
-        ChoiceSelection {
-            action: Choice::Action(Action::Underwear),
-            element: Choice::Element(Element::Grass),
-        },
-    );
-    player_one_inputs.insert(
-        KeyCode::KeyD,
-        ChoiceSelection {
-            action: Choice::Action(Action::Hand),
-            element: Choice::Element(Element::Fire),
-        },
-    );
-
-    let mut player_two_inputs: HashMap<KeyCode, ChoiceSelection> = HashMap::new();
-    player_two_inputs.insert(
-        KeyCode::KeyJ,
-        ChoiceSelection {
-            action: Choice::Action(Action::Toilet),
-            element: Choice::Element(Element::Water),
-        },
-    );
-    player_two_inputs.insert(
-        KeyCode::KeyK,
-        ChoiceSelection {
-            action: Choice::Action(Action::Underwear),
-            element: Choice::Element(Element::Grass),
-        },
+    game_data.state = MatchState::new(
+        rulesets.get(settings.variant, &ruleset_assets),
+        *rules,
+        settings.set_length.rounds_to_win(),
     );
-    player_two_inputs.insert(
-        KeyCode::KeyL,
-        ChoiceSelection {
-            action: Choice::Action(Action::Hand),
-            element: Choice::Element(Element::Fire),
-        },
-    );
-
-    game_data.player_one.input = PlayerInput::new(player_one_inputs);
-    // Only init controls for Two Player Mode
-    if settings.game_mode == GameMode::TwoPlayer {
-        game_data.player_two.input = PlayerInput::new(player_two_inputs);
+    // Replays and bot matches are not driven by the keyboard or controllers
+    if settings.game_mode.human(Player::One) {
+        game_data.player_one.input =
+            PlayerInput::new(&bindings.player_one, bindings.priority, settings.variant);
     }
+    // Only init controls for Two Player Mode, or when playing Blue online
+    if settings.game_mode.human(Player::Two) {
+        game_data.player_two.input =
+            PlayerInput::new(&bindings.player_two, bindings.priority, settings.variant);
+    }
+
+    game_data.player_one.blind = settings.blind_picks;
+    game_data.player_two.blind = settings.blind_picks;
 
     game_data.player_one.starting_pos = Vec3::new(-360.0, -100.0, 0.0);
     game_data.player_two.starting_pos = Vec3::new(360.0, -100.0, 0.0);
@@ -398,6 +450,8 @@ fn update_player_element(sprite: &mut Sprite, element: &Choice, player_asset: &P
         Choice::Element(Element::Fire) => sprite.image = player_asset.fire.clone(),
         Choice::Element(Element::Water) => sprite.image = player_asset.water.clone(),
         Choice::Element(Element::Grass) => sprite.image = player_asset.grass.clone(),
+        Choice::Element(Element::Metal) => sprite.image = player_asset.metal.clone(),
+        Choice::Element(Element::Earth) => sprite.image = player_asset.earth.clone(),
         _ => sprite.image = player_asset.neutral.clone(),
     }
 }
@@ -416,6 +470,7 @@ fn reset_player_elements(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn shake_players(
     mut commands: Commands,
     mut player_one_query: Query<(Entity, &mut Sprite), With<PlayerOne>>,
@@ -489,3 +544,66 @@ fn shake_player(
     sprite.color = Color::WHITE;
     commands.entity(*entity).insert(Animator::new(shake));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(priority: PressPriority) -> PlayerInput {
+        let bindings = Bindings::default();
+        PlayerInput::new(&bindings.player_one, priority, GameVariant::Classic)
+    }
+
+    fn held_element(input: &PlayerInput) -> Option<Choice> {
+        input.held().map(|(choice, _)| choice.element)
+    }
+
+    const FIRST: Choice = Choice::Element(CHOICES[0].0);
+    const SECOND: Choice = Choice::Element(CHOICES[1].0);
+
+    #[test]
+    fn priority_follows_press_order() {
+        for (priority, expected) in [
+            (PressPriority::MostRecent, SECOND),
+            (PressPriority::FirstPressed, FIRST),
+        ] {
+            let mut input = input(priority);
+            let mut keys = ButtonInput::default();
+            keys.press(KeyCode::KeyA);
+            input.update(&keys, None, 0.0);
+            keys.press(KeyCode::KeyS);
+            input.update(&keys, None, 1.0);
+            assert_eq!(held_element(&input), Some(expected));
+        }
+    }
+
+    #[test]
+    fn release_falls_back_to_the_other_held_choice() {
+        for priority in [PressPriority::MostRecent, PressPriority::FirstPressed] {
+            let mut input = input(priority);
+            let mut keys = ButtonInput::default();
+            keys.press(KeyCode::KeyA);
+            input.update(&keys, None, 0.0);
+            keys.press(KeyCode::KeyS);
+            input.update(&keys, None, 1.0);
+            keys.release(KeyCode::KeyS);
+            input.update(&keys, None, 2.0);
+            assert_eq!(held_element(&input), Some(FIRST));
+            keys.release(KeyCode::KeyA);
+            input.update(&keys, None, 3.0);
+            assert_eq!(held_element(&input), None);
+        }
+    }
+
+    #[test]
+    fn same_frame_presses_tie_break_the_same_way() {
+        for priority in [PressPriority::MostRecent, PressPriority::FirstPressed] {
+            let mut input = input(priority);
+            let mut keys = ButtonInput::default();
+            keys.press(KeyCode::KeyS);
+            keys.press(KeyCode::KeyA);
+            input.update(&keys, None, 0.0);
+            assert_eq!(held_element(&input), Some(FIRST));
+        }
+    }
+}
@@ -1,29 +1,198 @@
 use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use rand::seq::IteratorRandom;
 use rand::Rng;
 
 use crate::{
     combo::GameData,
-    config::{BOT_TIME_MAX, BOT_TIME_MIN},
-    events::{SelectActionEvent, SelectElementEvent},
+    config::{
+        BOT_COMMIT_MARGIN, BOT_FEINT_EASY, BOT_FEINT_HARD, BOT_FEINT_NORMAL, BOT_LOCK_DELAY,
+        BOT_MAX_FEINTS, BOT_TELL_EASY, BOT_TELL_HARD, BOT_TELL_NORMAL, BOT_TIME_EASY,
+        BOT_TIME_HARD, BOT_TIME_NORMAL,
+    },
+    events::{ExchangeEvent, SelectActionEvent, SelectElementEvent},
+    flow::countdown::Countdown,
+    helper::despawn,
+    opponents::{OpponentHandles, PersonalityAsset},
+    personality::{Personality, PersonalityBot},
+    rng::GameRng,
+    rules::MatchRules,
+    ruleset::{RulesetAsset, RulesetHandles},
     schedule::GameSet,
-    settings::{GameMode, GameSettings},
+    settings::{BotDifficulty, GameMode, GameSettings},
+    solver::Solver,
     state::{GameState, UiState},
-    types::{Action, Choice, Element, Player},
+    strategy::{for_difficulty, BotStrategy, MatchView, UnexploitableBot},
+    types::{Choice, Player},
 };
 
-#[derive(Component, Debug)]
+// How a bot times its picks within a countdown
+#[derive(Debug, Copy, Clone)]
+struct PickStyle {
+    // Range of seconds before the first pick
+    reaction_time: (f32, f32),
+    feint_chance: f64,
+    tell_chance: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Step {
+    // A pick the bot doesn't mean
+    Feint,
+    Real,
+}
+
+// The picks a bot will show during one countdown: a first pick, any feints,
+// then a commit to its real pick just before time runs out
+#[derive(Debug, Default)]
+struct PickPlan {
+    // Seconds into the countdown, in order
+    moments: Vec<f32>,
+    done: usize,
+    // Whether the first pick gives the real one away
+    tell: bool,
+    real: Option<Choice>,
+}
+
+impl PickPlan {
+    fn new(style: &PickStyle, countdown_time: f32, rng: &mut impl Rng) -> Self {
+        let commit = (countdown_time - BOT_COMMIT_MARGIN).max(0.0);
+        let (min, max) = style.reaction_time;
+        let first = rng.gen_range(min..=max).min(commit);
+        let feints = (0..BOT_MAX_FEINTS)
+            .filter(|_| rng.gen_bool(style.feint_chance.clamp(0.0, 1.0)))
+            .count();
+
+        let mut moments = vec![first];
+        if feints > 0 {
+            let mut middle: Vec<f32> = (1..feints).map(|_| rng.gen_range(first..=commit)).collect();
+            middle.sort_by(f32::total_cmp);
+            moments.extend(middle);
+            moments.push(commit);
+        }
+        Self {
+            moments,
+            done: 0,
+            tell: feints == 0 || rng.gen_bool(style.tell_chance.clamp(0.0, 1.0)),
+            real: None,
+        }
+    }
+
+    fn next_step(&mut self, elapsed: f32) -> Option<Step> {
+        let moment = *self.moments.get(self.done)?;
+        if moment > elapsed {
+            return None;
+        }
+        self.done += 1;
+        let last = self.done == self.moments.len();
+        Some(if last || (self.done == 1 && self.tell) {
+            Step::Real
+        } else {
+            Step::Feint
+        })
+    }
+
+    // Brings everything still planned forward to `by` at the latest
+    fn hurry(&mut self, by: f32) {
+        for moment in self.moments.iter_mut().skip(self.done) {
+            *moment = moment.min(by);
+        }
+    }
+
+    // The real pick has been shown and nothing else is planned
+    fn finished(&self) -> bool {
+        !self.moments.is_empty() && self.done == self.moments.len()
+    }
+}
+
+#[derive(Component)]
 pub struct ComputerPlayer {
-    timer: Timer,
+    player: Player,
+    style: PickStyle,
+    plan: PickPlan,
+    strategy: Box<dyn BotStrategy>,
 }
 
 impl ComputerPlayer {
-    pub fn reset(&mut self) {
-        self.timer = Timer::from_seconds(self.get_random_duration(), TimerMode::Repeating);
+    pub fn new(player: Player, difficulty: BotDifficulty) -> Self {
+        let (reaction_time, feint_chance, tell_chance) = match difficulty {
+            BotDifficulty::Easy => (BOT_TIME_EASY, BOT_FEINT_EASY, BOT_TELL_EASY),
+            BotDifficulty::Normal | BotDifficulty::Adaptive | BotDifficulty::Unexploitable => {
+                (BOT_TIME_NORMAL, BOT_FEINT_NORMAL, BOT_TELL_NORMAL)
+            }
+            BotDifficulty::Hard => (BOT_TIME_HARD, BOT_FEINT_HARD, BOT_TELL_HARD),
+        };
+        Self {
+            player,
+            style: PickStyle {
+                reaction_time,
+                feint_chance,
+                tell_chance,
+            },
+            plan: PickPlan::default(),
+            strategy: match difficulty {
+                BotDifficulty::Unexploitable => Box::new(UnexploitableBot::precomputed()),
+                _ => for_difficulty(difficulty),
+            },
+        }
+    }
+
+    pub fn with_personality(player: Player, personality: Personality) -> Self {
+        Self {
+            player,
+            style: PickStyle {
+                reaction_time: personality.reaction_time,
+                feint_chance: personality.feint_chance,
+                tell_chance: personality.tell_chance,
+            },
+            plan: PickPlan::default(),
+            strategy: Box::new(PersonalityBot::new(personality)),
+        }
     }
 
-    fn get_random_duration(&self) -> f32 {
-        let mut rng = rand::thread_rng();
-        rng.gen_range(BOT_TIME_MIN..BOT_TIME_MAX)
+    // Works through the plan up to `elapsed`, returning the pick to show if there is a new one
+    fn next_pick(
+        &mut self,
+        elapsed: f32,
+        shown: Choice,
+        options: &[Choice],
+        rng: &mut GameRng,
+        mut decide: impl FnMut(&mut dyn BotStrategy, &mut GameRng) -> Choice,
+    ) -> Option<Choice> {
+        let mut pick = None;
+        while let Some(step) = self.plan.next_step(elapsed) {
+            let shown = pick.unwrap_or(shown);
+            pick = Some(match step {
+                Step::Real => *self
+                    .plan
+                    .real
+                    .get_or_insert_with(|| decide(&mut *self.strategy, rng)),
+                Step::Feint => options
+                    .iter()
+                    .copied()
+                    .filter(|option| *option != shown)
+                    .choose(rng)
+                    .unwrap_or(shown),
+            });
+        }
+        pick
+    }
+
+    fn view<'a>(&self, game_data: &'a GameData, hide_opponent: bool) -> MatchView<'a> {
+        let opponent_element = if hide_opponent {
+            Choice::None
+        } else {
+            game_data
+                .player(self.player.opponent())
+                .choice_selection
+                .element
+        };
+        MatchView {
+            player: self.player,
+            state: &game_data.state,
+            own: game_data.player(self.player).choice_selection,
+            opponent_element,
+        }
     }
 }
 
@@ -32,6 +201,10 @@ pub struct ComputerPlugin;
 impl Plugin for ComputerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::GameStart), setup.in_set(GameSet::Flow));
+        app.add_systems(
+            OnEnter(UiState::Countdown),
+            plan_picks.in_set(GameSet::Flow),
+        );
         app.add_systems(
             Update,
             update_bot_element
@@ -46,14 +219,85 @@ impl Plugin for ComputerPlugin {
                 .run_if(in_state(GameState::SelectAction))
                 .in_set(GameSet::Ui),
         );
+        app.add_systems(
+            OnEnter(GameState::SelectAction),
+            observe_element.in_set(GameSet::Flow),
+        );
+        app.add_systems(Update, observe_exchanges.in_set(GameSet::Flow));
+        app.add_systems(
+            OnExit(GameState::GameOver),
+            despawn::<ComputerPlayer>.in_set(GameSet::Flow),
+        );
     }
 }
 
-fn setup(mut commands: Commands, settings: Res<GameSettings>) {
-    if settings.game_mode == GameMode::SinglePlayer {
-        commands.spawn(ComputerPlayer {
-            timer: Timer::from_seconds(0.5, TimerMode::Repeating),
-        });
+fn setup(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+    opponents: Res<OpponentHandles>,
+    personalities: Res<Assets<PersonalityAsset>>,
+    rulesets: Res<RulesetHandles>,
+    ruleset_assets: Res<Assets<RulesetAsset>>,
+) {
+    match settings.game_mode {
+        GameMode::SinglePlayer => {
+            let personality = settings
+                .opponent
+                .as_ref()
+                .and_then(|name| opponents.find(name, &personalities));
+            match personality {
+                Some(personality) => {
+                    info!(
+                        "Opponent: {} ({})",
+                        personality.name, personality.description
+                    );
+                    commands.spawn(ComputerPlayer::with_personality(Player::Two, personality));
+                }
+                None => {
+                    info!("Bot difficulty: {}", settings.difficulty.label());
+                    commands.spawn(ComputerPlayer::new(Player::Two, settings.difficulty));
+                }
+            }
+        }
+        GameMode::BotVsBot => {
+            info!(
+                "{} bot vs {} bot",
+                settings.red_bot.label(),
+                settings.difficulty.label()
+            );
+            commands.spawn(ComputerPlayer::new(Player::One, settings.red_bot));
+            commands.spawn(ComputerPlayer::new(Player::Two, settings.difficulty));
+        }
+        GameMode::TwoPlayer | GameMode::Replay | GameMode::Online(_) => (),
+    }
+
+    // Solve the match before the first pick, without holding up the frame
+    let unexploitable = match settings.game_mode {
+        GameMode::SinglePlayer => settings.difficulty == BotDifficulty::Unexploitable,
+        GameMode::BotVsBot => {
+            [settings.red_bot, settings.difficulty].contains(&BotDifficulty::Unexploitable)
+        }
+        _ => false,
+    };
+    let ruleset = rulesets.get(settings.variant, &ruleset_assets);
+    if unexploitable && Solver::cached(&ruleset, &rules).is_none() {
+        let rules = *rules;
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                Solver::shared(&ruleset, &rules);
+            })
+            .detach();
+    }
+}
+
+fn plan_picks(
+    mut query: Query<&mut ComputerPlayer>,
+    rules: Res<MatchRules>,
+    mut rng: ResMut<GameRng>,
+) {
+    for mut computer in &mut query {
+        computer.plan = PickPlan::new(&computer.style, rules.countdown_time, &mut *rng);
     }
 }
 
@@ -62,21 +306,36 @@ fn update_bot_element(
     mut game_data: ResMut<GameData>,
     mut writer: EventWriter<SelectElementEvent>,
     settings: Res<GameSettings>,
-    time: Res<Time>,
+    countdown: Res<Countdown>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if settings.game_mode == GameMode::TwoPlayer {
-        return;
-    }
-    let Ok(mut computer) = query.get_single_mut() else {
-        return;
-    };
-    computer.timer.tick(time.delta());
-    if computer.timer.just_finished() {
-        let random_element = Choice::Element(Element::random());
-        game_data
-            .player_two
-            .select_element(Player::Two, random_element, &mut writer);
-        computer.reset();
+    let elapsed = countdown.timer.elapsed_secs();
+    let options: Vec<Choice> = game_data
+        .state
+        .ruleset
+        .elements()
+        .into_iter()
+        .map(Choice::Element)
+        .collect();
+    for mut computer in &mut query {
+        let player = computer.player;
+        // Once the opponent locks in, don't keep them waiting
+        if game_data.player(player.opponent()).locked {
+            computer.plan.hurry(elapsed + BOT_LOCK_DELAY);
+        }
+        let shown = game_data.player(player).choice_selection.element;
+        // Blind picks keep the opponent's element hidden from the bot too
+        let view = computer.view(&game_data, settings.blind_picks);
+        let pick = computer.next_pick(elapsed, shown, &options, &mut rng, |strategy, rng| {
+            Choice::Element(strategy.choose_element(&view, rng))
+        });
+        let player_data = game_data.player_mut(player);
+        if let Some(choice) = pick {
+            player_data.select_element(player, choice, &mut writer);
+        }
+        if computer.plan.finished() {
+            player_data.lock(player_data.choice_selection.element);
+        }
     }
 }
 
@@ -84,22 +343,90 @@ fn update_bot_action(
     mut query: Query<&mut ComputerPlayer>,
     mut game_data: ResMut<GameData>,
     mut writer: EventWriter<SelectActionEvent>,
-    settings: Res<GameSettings>,
-    time: Res<Time>,
+    countdown: Res<Countdown>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if settings.game_mode == GameMode::TwoPlayer {
-        return;
+    let elapsed = countdown.timer.elapsed_secs();
+    let options: Vec<Choice> = game_data
+        .state
+        .ruleset
+        .actions()
+        .into_iter()
+        .map(Choice::Action)
+        .collect();
+    for mut computer in &mut query {
+        let player = computer.player;
+        // Once the opponent locks in, don't keep them waiting
+        if game_data.player(player.opponent()).locked {
+            computer.plan.hurry(elapsed + BOT_LOCK_DELAY);
+        }
+        let shown = game_data.player(player).choice_selection.action;
+        // Both elements have been revealed by the time actions are picked
+        let view = computer.view(&game_data, false);
+        let pick = computer.next_pick(elapsed, shown, &options, &mut rng, |strategy, rng| {
+            Choice::Action(strategy.choose_action(&view, rng))
+        });
+        let player_data = game_data.player_mut(player);
+        if let Some(choice) = pick {
+            player_data.select_action(player, choice, &mut writer);
+        }
+        if computer.plan.finished() {
+            player_data.lock(player_data.choice_selection.action);
+        }
     }
-    let Ok(mut computer) = query.get_single_mut() else {
+}
+
+// Elements are revealed once per element phase, before the first action
+fn observe_element(mut query: Query<&mut ComputerPlayer>, game_data: Res<GameData>) {
+    if game_data.state.action > 0 {
         return;
-    };
-    computer.timer.tick(time.delta());
-    if computer.timer.just_finished() {
-        let element = game_data.player_two.choice_selection.element;
-        let random_action = Choice::Action(Action::weighted(&element));
-        game_data
-            .player_two
-            .select_action(Player::Two, random_action, &mut writer);
-        computer.reset();
+    }
+    for mut computer in &mut query {
+        let view = computer.view(&game_data, false);
+        if let Choice::Element(element) = view.opponent_element {
+            computer.strategy.observe_element(&view, element);
+        }
+    }
+}
+
+fn observe_exchanges(
+    mut query: Query<&mut ComputerPlayer>,
+    mut reader: EventReader<ExchangeEvent>,
+    game_data: Res<GameData>,
+) {
+    for event in reader.read() {
+        for mut computer in &mut query {
+            let opponent_action = match computer.player {
+                Player::One => event.player_two.action,
+                Player::Two => event.player_one.action,
+            };
+            if let Choice::Action(action) = opponent_action {
+                let view = computer.view(&game_data, false);
+                computer
+                    .strategy
+                    .observe_exchange(&view, action, event.outcome);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hurry_brings_the_plan_forward() {
+        let mut plan = PickPlan {
+            moments: vec![0.5, 1.5, 3.0],
+            tell: true,
+            ..default()
+        };
+        assert_eq!(plan.next_step(0.6), Some(Step::Real));
+        plan.hurry(1.0);
+        assert_eq!(plan.moments, vec![0.5, 1.0, 1.0]);
+        assert_eq!(plan.next_step(0.9), None);
+        assert_eq!(plan.next_step(1.0), Some(Step::Feint));
+        assert_eq!(plan.next_step(1.0), Some(Step::Real));
+        assert!(plan.finished());
     }
 }
@@ -20,15 +20,15 @@ pub const SIZE_M: f32 = 32.0;
 pub const SIZE_S: f32 = 24.0;
 // pub const SIZE_XS: f32 = 16.0;
 
-// ====== IMAGES ======
-pub const CONTROLS_WIDTH: f32 = 377.0;
-pub const CONTROLS_HEIGHT: f32 = 265.0;
-
 // ====== BUTTONS ======
 pub const BUTTON_WIDTH: Val = Val::Px(200.0);
 pub const BUTTON_HEIGHT: Val = Val::Px(50.0);
 pub const BUTTON_BORDER: UiRect = UiRect::all(Val::Px(2.0));
 pub const BORDER_RADIUS: Val = Val::Px(5.0);
+// Three buttons and their gaps, so the title menu wraps into short rows
+pub const MENU_GRID_WIDTH: Val = Val::Px(616.0);
+// Side of the tappable choice buttons shown during a countdown
+pub const CHOICE_BUTTON_SIZE: f32 = 64.0;
 
 pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
@@ -56,8 +56,23 @@ pub const LOSS_COLOUR_SOLID: Color = Color::srgba(0.2, 0.2, 0.2, 1.0);
 pub const WON_COLOUR_TRANSPARENT: Color = Color::srgba(0.2, 0.8, 0.2, 0.0);
 pub const WON_COLOUR_SOLID: Color = Color::srgba(0.2, 0.8, 0.2, 1.0);
 
-pub const BOT_TIME_MIN: f32 = 0.2;
-pub const BOT_TIME_MAX: f32 = 1.2;
+// Range of seconds the bot waits between picks, per difficulty
+pub const BOT_TIME_EASY: (f32, f32) = (0.8, 2.0);
+pub const BOT_TIME_NORMAL: (f32, f32) = (0.2, 1.2);
+pub const BOT_TIME_HARD: (f32, f32) = (0.1, 0.6);
+// Chance of each possible feint, per difficulty
+pub const BOT_FEINT_EASY: f64 = 0.1;
+pub const BOT_FEINT_NORMAL: f64 = 0.3;
+pub const BOT_FEINT_HARD: f64 = 0.5;
+// Chance that a bot which feints shows its real pick first, per difficulty
+pub const BOT_TELL_EASY: f64 = 0.8;
+pub const BOT_TELL_NORMAL: f64 = 0.5;
+pub const BOT_TELL_HARD: f64 = 0.2;
+pub const BOT_MAX_FEINTS: usize = 2;
+// Seconds before the countdown ends that a bot commits to its final pick
+pub const BOT_COMMIT_MARGIN: f32 = 0.3;
+// Seconds a bot takes to lock in once its opponent has
+pub const BOT_LOCK_DELAY: f32 = 0.4;
 
 // Time Allowed to Choose an Element / Action
 pub const COUNTDOWN_TIME: f32 = 4.0;
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-use crate::types::{Choice, Player};
+use crate::rules::ChoiceSelection;
+use crate::types::{Choice, Outcome, Player};
 
 #[derive(Event, Debug, Default)]
 pub struct ApplyEffectsEvent;
@@ -28,6 +29,31 @@ impl SelectActionEvent {
     }
 }
 
+// Sent when a player lets the countdown run out without picking
+#[derive(Event, Debug)]
+pub struct TimeoutEvent {
+    pub player: Player,
+}
+
+// Sent when an online player's reveal doesn't match what they committed to
+#[derive(Event, Debug)]
+pub struct ForfeitEvent {
+    pub player: Player,
+}
+
+// Sent after an exchange of actions has been resolved and damage applied
+#[derive(Event, Debug)]
+pub struct ExchangeEvent {
+    pub player_one: ChoiceSelection,
+    pub player_two: ChoiceSelection,
+    pub outcome: Outcome,
+    pub health: (i32, i32),
+    pub advantage: Player,
+    // Who timed out on their element and action picks
+    pub element_timeouts: (bool, bool),
+    pub action_timeouts: (bool, bool),
+}
+
 pub struct EventsPlugin;
 
 impl Plugin for EventsPlugin {
@@ -35,5 +61,8 @@ impl Plugin for EventsPlugin {
         app.add_event::<ApplyEffectsEvent>();
         app.add_event::<SelectElementEvent>();
         app.add_event::<SelectActionEvent>();
+        app.add_event::<ExchangeEvent>();
+        app.add_event::<TimeoutEvent>();
+        app.add_event::<ForfeitEvent>();
     }
 }
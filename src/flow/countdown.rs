@@ -3,6 +3,7 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use crate::{
+    combo::GameData,
     config::{SIZE_XXL, START_STATE},
     globals::UiAssets,
     helper::{despawn, hide, show},
@@ -10,9 +11,14 @@ use crate::{
     state::{GameState, UiState},
 };
 
+// How far short of the end a held countdown waits
+const HOLD_MARGIN: Duration = Duration::from_millis(50);
+
 #[derive(Resource)]
 pub struct Countdown {
     pub timer: Timer,
+    // Online countdowns are held open until both final picks have arrived
+    pub held: bool,
 }
 
 impl Countdown {
@@ -21,8 +27,22 @@ impl Countdown {
     }
 
     pub fn tick(&mut self, duration: Duration) {
+        let duration = if self.held {
+            duration.min(self.timer.remaining().saturating_sub(HOLD_MARGIN))
+        } else {
+            duration
+        };
         self.timer.tick(duration);
     }
+
+    // Runs the timer out now, as if the time had passed
+    pub fn finish(&mut self) {
+        if self.held {
+            return;
+        }
+        let remaining = self.timer.remaining();
+        self.timer.tick(remaining);
+    }
 }
 
 #[derive(Component, Debug, Default)]
@@ -35,6 +55,7 @@ impl Default for Countdown {
     fn default() -> Self {
         Self {
             timer: Timer::from_seconds(0.0, TimerMode::Once),
+            held: false,
         }
     }
 }
@@ -49,7 +70,7 @@ impl Plugin for CountdownPlugin {
         // Showing, Updating, and Hiding the CountdownPopup
         app.add_systems(
             OnEnter(UiState::Countdown),
-            show::<CountdownPopup>.in_set(GameSet::Ui),
+            (show::<CountdownPopup>, unlock_picks).in_set(GameSet::Ui),
         );
         app.add_systems(
             Update,
@@ -97,6 +118,12 @@ fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
         ));
 }
 
+// Every countdown starts with both picks open
+fn unlock_picks(mut game_data: ResMut<GameData>) {
+    game_data.player_one.locked = false;
+    game_data.player_two.locked = false;
+}
+
 fn update_timer(mut query: Query<&mut Text, With<CountdownText>>, countdown: Res<Countdown>) {
     let Ok(mut timer) = query.get_single_mut() else {
         return;
@@ -1,12 +1,14 @@
 use bevy::prelude::*;
 use std::time::Duration;
 
-mod countdown;
+pub mod countdown;
 mod resolve_action;
+mod reveal;
 mod round_over;
 mod round_start;
 mod select_action;
 mod select_element;
+pub mod timeout;
 
 use countdown::CountdownPlugin;
 use resolve_action::ResolveActionPlugin;
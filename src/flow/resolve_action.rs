@@ -6,7 +6,7 @@ use bevy_tweening::{Animator, Delay, Sequence, TweenCompleted};
 const RESOLVE_COMPLETE_ID: u64 = 1;
 const BACK_TO_ELEMENT: u64 = 2;
 const EVENT_LOOP: u64 = 3;
-const EVENT_COMBO_BREAKER: u64 = 4;
+const NEXT_ROUND: u64 = 5;
 const EVENT_REMOVE: u64 = 900;
 const EVENT_AUDIO: u64 = 1000;
 
@@ -16,14 +16,16 @@ use crate::{
         won_sequence,
     },
     camera::{SCREEN_X, SCREEN_Y},
-    combo::{GameData, ResolveResult},
+    combo::GameData,
     config::{
         ANIM_FADE_IN, ANIM_FADE_IN_COLOUR, ANIM_FADE_OUT_COLOUR, ANIM_SCROLL_LEFT,
         ANIM_SCROLL_RIGHT, ANIM_STAY, SIZE_M, SIZE_S, TRANSPARENT,
     },
-    events::ApplyEffectsEvent,
+    events::{ApplyEffectsEvent, ExchangeEvent},
     globals::{AudioAssets, UiAssets},
     helper::{despawn, get_random},
+    rng::CosmeticRng,
+    rules::{MatchResult, Transition},
     schedule::GameSet,
     state::GameState,
     types::{Action, Choice, Outcome, Player},
@@ -68,7 +70,22 @@ fn on_enter(
     window: Query<&Window, With<PrimaryWindow>>,
     ui_assets: Res<UiAssets>,
     audio_assets: Res<AudioAssets>,
+    mut rng: ResMut<CosmeticRng>,
 ) {
+    // A forfeit settled the round, so there is no exchange to show
+    if game_data.state.decided() {
+        commands.spawn((
+            ResolveActionPopup,
+            Node::default(),
+            BackgroundColor(TRANSPARENT),
+            Animator::new(
+                Delay::<BackgroundColor>::new(Duration::from_millis(ANIM_FADE_IN))
+                    .with_completed_event(RESOLVE_COMPLETE_ID),
+            ),
+        ));
+        return;
+    }
+
     let window = window.single();
     let width = window.resolution.width();
     let height = window.resolution.height();
@@ -111,8 +128,8 @@ fn on_enter(
         );
 
     let audio = match result.outcome {
-        Outcome::Draw => get_random(&audio_assets.draw).clone(),
-        _ => get_random(&audio_assets.laugh).clone(),
+        Outcome::Draw => get_random(&audio_assets.draw, &mut *rng).clone(),
+        _ => get_random(&audio_assets.laugh, &mut *rng).clone(),
     };
 
     commands
@@ -211,16 +228,19 @@ fn spawn_image_node(
         });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_next_flow(
     mut commands: Commands,
     mut reader: EventReader<TweenCompleted>,
     mut writer: EventWriter<ApplyEffectsEvent>,
+    mut exchange_writer: EventWriter<ExchangeEvent>,
     mut game_data: ResMut<GameData>,
     mut game_flow: ResMut<NextState<GameState>>,
     popup_item_query: Query<Entity, With<ActionPopupItem>>,
     popup_query: Query<Entity, With<ResolveActionPopup>>,
     ui_assets: Res<UiAssets>,
     audio_assets: Res<AudioAssets>,
+    mut rng: ResMut<CosmeticRng>,
 ) {
     for event in reader.read() {
         match event.user_data {
@@ -230,161 +250,184 @@ fn update_next_flow(
                 }
                 // Also play audio here
                 let audio = match game_data.get_action_result().choice {
-                    Choice::Action(Action::Hand) => get_random(&audio_assets.nuggie).clone(),
-                    Choice::Action(Action::Toilet) => get_random(&audio_assets.swirly).clone(),
-                    Choice::Action(Action::Underwear) => get_random(&audio_assets.wedgie).clone(),
-                    _ => get_random(&audio_assets.laugh).clone(),
+                    Choice::Action(Action::Hand) => {
+                        get_random(&audio_assets.nuggie, &mut *rng).clone()
+                    }
+                    Choice::Action(Action::Toilet) => {
+                        get_random(&audio_assets.swirly, &mut *rng).clone()
+                    }
+                    Choice::Action(Action::Underwear) => {
+                        get_random(&audio_assets.wedgie, &mut *rng).clone()
+                    }
+                    _ => get_random(&audio_assets.laugh, &mut *rng).clone(),
                 };
                 if let Ok(popup) = popup_query.get_single() {
                     commands.entity(popup).insert(AudioPlayer::new(audio));
                 }
             }
             RESOLVE_COMPLETE_ID => {
-                let result = game_data.get_action_result();
-                game_data.process_turn();
+                let player_one = game_data.player_one.choice_selection;
+                let player_two = game_data.player_two.choice_selection;
+                let element_timeouts = (
+                    game_data.player_one.element_timed_out,
+                    game_data.player_two.element_timed_out,
+                );
+                let action_timeouts = (
+                    game_data.player_one.action_timed_out,
+                    game_data.player_two.action_timed_out,
+                );
+                let report = game_data.process_turn();
+                info!(
+                    "{:?} won with {} for {} damage",
+                    report.result.outcome, report.result.choice, report.damage
+                );
 
-                writer.send(ApplyEffectsEvent::default());
-                if game_data.can_end_game() {
-                    game_over(&mut game_flow);
-                } else {
-                    match (&result.outcome, game_data.action) {
-                        (Outcome::Draw, 1) => {
-                            back_to_element(&mut commands, &ui_assets, &audio_assets)
-                        }
-                        (Outcome::Draw, _) => loop_action(
-                            &mut commands,
-                            &result,
-                            &game_data,
-                            &mut game_flow,
-                            &ui_assets,
-                            &audio_assets,
-                        ),
-                        (Outcome::PlayerOne, 1) => advantage(
-                            &mut commands,
-                            &result,
-                            &mut game_data,
-                            &ui_assets,
-                            &audio_assets,
-                        ),
-                        (Outcome::PlayerTwo, 1) => advantage(
-                            &mut commands,
-                            &result,
-                            &mut game_data,
-                            &ui_assets,
-                            &audio_assets,
-                        ),
-                        (Outcome::PlayerOne, _) => loop_action(
-                            &mut commands,
-                            &result,
-                            &game_data,
-                            &mut game_flow,
-                            &ui_assets,
-                            &audio_assets,
-                        ),
-                        (Outcome::PlayerTwo, _) => loop_action(
-                            &mut commands,
-                            &result,
-                            &game_data,
-                            &mut game_flow,
-                            &ui_assets,
-                            &audio_assets,
-                        ),
+                writer.send(ApplyEffectsEvent);
+                exchange_writer.send(ExchangeEvent {
+                    player_one,
+                    player_two,
+                    outcome: report.result.outcome,
+                    health: (
+                        game_data.state.player_one.health,
+                        game_data.state.player_two.health,
+                    ),
+                    advantage: game_data.state.advantage,
+                    element_timeouts,
+                    action_timeouts,
+                });
+                match report.transition {
+                    Transition::GameOver => game_over(&mut game_flow),
+                    Transition::NoAdvantage => {
+                        back_to_element(&mut commands, &ui_assets, &audio_assets)
+                    }
+                    Transition::Advantage(player) => {
+                        advantage(&mut commands, player, &ui_assets, &audio_assets)
+                    }
+                    Transition::ComboBreaker => {
+                        combo_breaker(&mut commands, &ui_assets, &audio_assets)
+                    }
+                    Transition::Continue => game_flow.set(GameState::SelectAction),
+                    Transition::SuddenDeath => sudden_death(
+                        &mut commands,
+                        &ui_assets,
+                        get_random(&audio_assets.draw, &mut *rng).clone(),
+                    ),
+                    Transition::RoundOver(result) => {
+                        round_over(&mut commands, result, &ui_assets, &audio_assets, &mut rng)
                     }
                 }
             }
             BACK_TO_ELEMENT => {
-                game_data.action = 0;
-                game_flow.set(GameState::SelectElement);
-            }
-            EVENT_COMBO_BREAKER => {
-                game_data.action = 0;
                 game_flow.set(GameState::SelectElement);
             }
             EVENT_LOOP => {
                 game_flow.set(GameState::SelectAction);
             }
+            NEXT_ROUND => {
+                game_data.next_round();
+                writer.send(ApplyEffectsEvent);
+                game_flow.set(GameState::RoundStart);
+            }
             _ => (),
         }
     }
 }
 
-fn back_to_element(
-    commands: &mut Commands,
-    ui_assets: &Res<UiAssets>,
-    audio_assets: &Res<AudioAssets>,
-) {
+fn back_to_element(commands: &mut Commands, ui_assets: &UiAssets, audio_assets: &AudioAssets) {
     transition_title(
         commands,
         "No Advantage",
         "Restarting Round",
         BACK_TO_ELEMENT,
-        &ui_assets,
+        ui_assets,
         audio_assets.no_advantage.clone(),
     );
 }
 
-fn loop_action(
-    commands: &mut Commands,
-    result: &ResolveResult,
-    game_data: &ResMut<GameData>,
-    game_flow: &mut ResMut<NextState<GameState>>,
-    ui_assets: &Res<UiAssets>,
-    audio_assets: &Res<AudioAssets>,
-) {
-    match (&result.outcome, &game_data.advantage) {
-        (Outcome::PlayerOne, Player::Two) => combo_breaker(commands, &ui_assets, &audio_assets),
-        (Outcome::PlayerTwo, Player::One) => combo_breaker(commands, &ui_assets, &audio_assets),
-        _ => game_flow.set(GameState::SelectAction),
-    }
-}
-
-fn combo_breaker(
-    commands: &mut Commands,
-    ui_assets: &Res<UiAssets>,
-    audio_assets: &Res<AudioAssets>,
-) {
+fn combo_breaker(commands: &mut Commands, ui_assets: &UiAssets, audio_assets: &AudioAssets) {
     transition_title(
         commands,
         "COMBO BREAKER!",
         "Restarting Round",
         BACK_TO_ELEMENT,
-        &ui_assets,
+        ui_assets,
         audio_assets.combo_breaker.clone(),
     );
 }
 
 fn advantage(
     commands: &mut Commands,
-    result: &ResolveResult,
-    game_data: &mut ResMut<GameData>,
-    ui_assets: &Res<UiAssets>,
-    audio_assets: &Res<AudioAssets>,
+    player: Player,
+    ui_assets: &UiAssets,
+    audio_assets: &AudioAssets,
 ) {
-    match result.outcome {
-        Outcome::PlayerOne => {
-            transition_title(
-                commands,
-                "Red has the Advantage",
-                "The combat will continue until Red loses",
-                EVENT_LOOP,
-                &ui_assets,
-                audio_assets.player_one_advantage.clone(),
-            );
-            game_data.advantage = Player::One
-        }
-        Outcome::PlayerTwo => {
-            transition_title(
-                commands,
-                "Blue has the Advantage",
-                "The combat will continue until Blue loses",
-                EVENT_LOOP,
-                &ui_assets,
-                audio_assets.player_two_advantage.clone(),
-            );
-            game_data.advantage = Player::Two
-        }
+    match player {
+        Player::One => transition_title(
+            commands,
+            "Red has the Advantage",
+            "The combat will continue until Red loses",
+            EVENT_LOOP,
+            ui_assets,
+            audio_assets.player_one_advantage.clone(),
+        ),
+        Player::Two => transition_title(
+            commands,
+            "Blue has the Advantage",
+            "The combat will continue until Blue loses",
+            EVENT_LOOP,
+            ui_assets,
+            audio_assets.player_two_advantage.clone(),
+        ),
+    }
+}
 
-        _ => (),
+fn sudden_death(commands: &mut Commands, ui_assets: &UiAssets, audio: Handle<AudioSource>) {
+    transition_title(
+        commands,
+        "SUDDEN DEATH!",
+        "One heart each, the next hit wins",
+        BACK_TO_ELEMENT,
+        ui_assets,
+        audio,
+    );
+}
+
+fn round_over(
+    commands: &mut Commands,
+    result: MatchResult,
+    ui_assets: &UiAssets,
+    audio_assets: &AudioAssets,
+    rng: &mut CosmeticRng,
+) {
+    match result {
+        MatchResult::Winner(Player::One) => transition_title(
+            commands,
+            "Red wins the round",
+            "Get ready for the next one",
+            NEXT_ROUND,
+            ui_assets,
+            audio_assets.player_one_wins.clone(),
+        ),
+        MatchResult::Winner(Player::Two) => transition_title(
+            commands,
+            "Blue wins the round",
+            "Get ready for the next one",
+            NEXT_ROUND,
+            ui_assets,
+            audio_assets.player_two_wins.clone(),
+        ),
+        MatchResult::Draw | MatchResult::DoubleKo => transition_title(
+            commands,
+            if result == MatchResult::DoubleKo {
+                "Double KO!"
+            } else {
+                "The round is a draw"
+            },
+            "Nobody takes the round",
+            NEXT_ROUND,
+            ui_assets,
+            get_random(&audio_assets.draw, rng).clone(),
+        ),
     }
 }
 
@@ -393,7 +436,7 @@ fn transition_title(
     title: &str,
     subtitle: &str,
     next_state: u64,
-    ui_assets: &Res<UiAssets>,
+    ui_assets: &UiAssets,
     audio: Handle<AudioSource>,
 ) {
     let tween_scale = scale_up().then(
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+use crate::{
+    combo::GameData,
+    events::{SelectActionEvent, SelectElementEvent},
+    state::{GameState, UiState},
+    types::{Choice, Player},
+};
+
+use super::countdown::Countdown;
+
+// With blind picks, send the held back selection feedback for both players at once
+pub fn reveal_hidden_picks(
+    countdown: Res<Countdown>,
+    current_ui_flow: Res<State<UiState>>,
+    current_game_flow: Res<State<GameState>>,
+    mut game_data: ResMut<GameData>,
+    mut element_writer: EventWriter<SelectElementEvent>,
+    mut action_writer: EventWriter<SelectActionEvent>,
+) {
+    if !countdown.timer.just_finished() || *current_ui_flow.get() != UiState::Countdown {
+        return;
+    }
+
+    for player in [Player::One, Player::Two] {
+        let player_data = game_data.player_mut(player);
+        if !player_data.blind {
+            continue;
+        }
+        player_data.hidden_pick = false;
+        let selection = player_data.choice_selection;
+        match current_game_flow.get() {
+            GameState::SelectElement if selection.element != Choice::None => {
+                element_writer.send(SelectElementEvent::new(player, selection.element));
+            }
+            GameState::SelectAction if selection.action != Choice::None => {
+                action_writer.send(SelectActionEvent::new(player));
+            }
+            _ => (),
+        }
+    }
+}
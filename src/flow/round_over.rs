@@ -4,7 +4,9 @@ use crate::{
     combo::GameData,
     config::{BUTTON_BORDER, BUTTON_HEIGHT, BUTTON_WIDTH, DARK, NORMAL_BUTTON, SIZE_S, SIZE_XL},
     globals::{AudioAssets, UiAssets},
-    helper::{despawn, handle_buttons},
+    helper::{despawn, get_random, handle_buttons},
+    rng::CosmeticRng,
+    rules::MatchResult,
     schedule::GameSet,
     state::GameState,
     types::Player,
@@ -36,16 +38,22 @@ fn on_enter(
     ui_assets: Res<UiAssets>,
     audio_assets: Res<AudioAssets>,
     game_data: Res<GameData>,
+    mut rng: ResMut<CosmeticRng>,
 ) {
-    let winner = game_data.get_winner();
-    let audio = match winner {
-        Player::One => audio_assets.player_one_wins.clone(),
-        Player::Two => audio_assets.player_two_wins.clone(),
+    let result = game_data.state.get_result();
+    let audio = match result {
+        MatchResult::Winner(Player::One) => audio_assets.player_one_wins.clone(),
+        MatchResult::Winner(Player::Two) => audio_assets.player_two_wins.clone(),
+        MatchResult::Draw | MatchResult::DoubleKo => {
+            get_random(&audio_assets.draw, &mut *rng).clone()
+        }
     };
 
-    let text = match winner {
-        Player::One => "Red Wins!",
-        Player::Two => "Blue Wins!",
+    let text = match result {
+        MatchResult::Winner(Player::One) => "Red Wins!",
+        MatchResult::Winner(Player::Two) => "Blue Wins!",
+        MatchResult::Draw => "It's a Draw!",
+        MatchResult::DoubleKo => "Double KO!",
     };
 
     // Spawn the Root Node
@@ -4,10 +4,12 @@ use bevy::prelude::*;
 use bevy_tweening::{Animator, Delay, TweenCompleted};
 
 use crate::animations::{fade_in, fade_out, scale_down, scale_up};
+use crate::combo::GameData;
 use crate::config::{ANIM_FADE_IN, ANIM_SCALE_DOWN, ANIM_SCALE_UP, SIZE_XXXL, TRANSPARENT};
 use crate::globals::AudioAssets;
 use crate::helper::despawn;
 use crate::schedule::GameSet;
+use crate::settings::{GameSettings, SetLength};
 use crate::state::UiState;
 use crate::{globals::UiAssets, state::GameState};
 
@@ -20,36 +22,49 @@ struct RoundStartPopup;
 
 impl Plugin for RoundStartPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameStart), on_enter.in_set(GameSet::Ui));
-        app.add_systems(
-            OnExit(GameState::GameStart),
-            despawn::<RoundStartPopup>.in_set(GameSet::Ui),
-        );
-        app.add_systems(
-            Update,
-            on_complete
-                .in_set(GameSet::Ui)
-                .run_if(in_state(GameState::GameStart)),
-        );
+        for state in [GameState::GameStart, GameState::RoundStart] {
+            app.add_systems(OnEnter(state), on_enter.in_set(GameSet::Ui));
+            app.add_systems(
+                OnExit(state),
+                despawn::<RoundStartPopup>.in_set(GameSet::Ui),
+            );
+            app.add_systems(
+                Update,
+                on_complete.in_set(GameSet::Ui).run_if(in_state(state)),
+            );
+        }
     }
 }
 
 fn on_enter(
     mut commands: Commands,
     mut ui_state: ResMut<NextState<UiState>>,
+    current_state: Res<State<GameState>>,
+    settings: Res<GameSettings>,
+    game_data: Res<GameData>,
     ui_assets: Res<UiAssets>,
     audio_assets: Res<AudioAssets>,
 ) {
     ui_state.set(UiState::Title);
+    // Sets announce the round before the usual intro
+    let mut titles = vec![];
+    if settings.set_length != SetLength::Single {
+        let round = match current_state.get() {
+            GameState::RoundStart => game_data.state.round,
+            _ => 1,
+        };
+        titles.push(format!("ROUND {}", round));
+    }
+    titles.push("SWIRLY".to_string());
+    titles.push("WHIRLY".to_string());
+
+    let title_duration = ANIM_SCALE_UP + ANIM_SCALE_DOWN;
     let background_animation = fade_in().then(
         Delay::new(Duration::from_millis(
-            (ANIM_SCALE_UP + ANIM_SCALE_DOWN) * 2 - ANIM_FADE_IN,
+            title_duration * titles.len() as u64 - ANIM_FADE_IN,
         ))
         .then(fade_out().with_completed_event(NEXT_STATE)),
     );
-    let first_animation = scale_up().then(scale_down());
-    let second_animation = Delay::new(Duration::from_millis(ANIM_SCALE_UP + ANIM_SCALE_DOWN))
-        .then(scale_up().then(scale_down()));
     // Fading Screen
     commands
         .spawn((
@@ -65,38 +80,31 @@ fn on_enter(
             Animator::new(background_animation),
         ))
         .with_children(|parent| {
-            parent.spawn((
-                Text::new("SWIRLY"),
-                TextFont {
-                    font: ui_assets.ms_pain.clone(),
-                    font_size: SIZE_XXXL,
-                    ..default()
-                },
-                Node {
-                    position_type: PositionType::Absolute,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-                Animator::new(first_animation),
-            ));
-            parent.spawn((
-                Text::new("WHIRLY"),
-                TextFont {
-                    font: ui_assets.ms_pain.clone(),
-                    font_size: SIZE_XXXL,
-                    ..default()
-                },
-                Transform {
-                    scale: Vec3::ZERO,
-                    ..default()
-                },
-                Node {
-                    position_type: PositionType::Absolute,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-                Animator::new(second_animation),
-            ));
+            for (i, title) in titles.into_iter().enumerate() {
+                let animation = match i {
+                    0 => scale_up().then(scale_down()),
+                    _ => Delay::new(Duration::from_millis(title_duration * i as u64))
+                        .then(scale_up().then(scale_down())),
+                };
+                parent.spawn((
+                    Text::new(title),
+                    TextFont {
+                        font: ui_assets.ms_pain.clone(),
+                        font_size: SIZE_XXXL,
+                        ..default()
+                    },
+                    Transform {
+                        scale: Vec3::ZERO,
+                        ..default()
+                    },
+                    Node {
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    Animator::new(animation),
+                ));
+            }
             parent.spawn(AudioPlayer::new(audio_assets.ready.clone()));
         });
 }
@@ -6,19 +6,21 @@ use bevy_tweening::{Animator, Delay, TweenCompleted};
 use crate::{
     animations::{fade_in, fade_out, scale_down, scale_up},
     combo::{GameData, PlayerData},
-    config::{
-        ANIM_FADE_IN, ANIM_SCALE_DOWN, ANIM_SCALE_UP, COUNTDOWN_TIME, REVEAL_TIME, SIZE_XXXL,
-        TRANSPARENT,
-    },
+    config::{ANIM_FADE_IN, ANIM_SCALE_DOWN, ANIM_SCALE_UP, SIZE_XXXL, TRANSPARENT},
     events::SelectElementEvent,
+    gamepad::PadAssignments,
     globals::{AudioAssets, UiAssets},
     helper::{despawn, get_random},
+    rng::CosmeticRng,
+    rules::MatchRules,
     schedule::GameSet,
     state::{GameState, UiState},
     types::{Choice, Element, Player},
 };
 
 use super::countdown::Countdown;
+use super::reveal::reveal_hidden_picks;
+use super::timeout::{resolve_timeouts, skip_decided};
 
 pub const COUNTDOWN_STATE: u64 = 200;
 
@@ -38,7 +40,15 @@ impl Plugin for SelectElementPlugin {
         );
         app.add_systems(
             Update,
-            (handle_countdown, handle_input, select_audio)
+            (
+                handle_input,
+                handle_countdown,
+                resolve_timeouts,
+                skip_decided,
+                reveal_hidden_picks,
+                select_audio,
+            )
+                .chain()
                 .in_set(GameSet::Ui)
                 .run_if(in_state(GameState::SelectElement)),
         );
@@ -104,6 +114,7 @@ fn on_enter(
         ));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_countdown(
     mut countdown: ResMut<Countdown>,
     current_ui_flow: Res<State<UiState>>,
@@ -111,58 +122,85 @@ fn handle_countdown(
     mut next_game_flow: ResMut<NextState<GameState>>,
     time: Res<Time>,
     mut reader: EventReader<TweenCompleted>,
+    rules: Res<MatchRules>,
+    game_data: Res<GameData>,
 ) {
-    countdown.tick(time.delta());
+    // Once both picks are locked in there is nothing left to wait for
+    if game_data.both_locked() && *current_ui_flow.get() == UiState::Countdown {
+        countdown.finish();
+    } else {
+        countdown.tick(time.delta());
+    }
 
     for event in reader.read() {
         if event.user_data == COUNTDOWN_STATE {
-            countdown.reset(Timer::from_seconds(COUNTDOWN_TIME, TimerMode::Once));
+            countdown.reset(Timer::from_seconds(rules.countdown_time, TimerMode::Once));
             next_ui_flow.set(UiState::Countdown);
         }
     }
 
-    if countdown.timer.just_finished() {
-        match current_ui_flow.get() {
-            // Go to the reveal after the countdown
-            UiState::Countdown => {
-                next_ui_flow.set(UiState::None);
-                countdown.reset(Timer::from_seconds(REVEAL_TIME, TimerMode::Once));
-                next_game_flow.set(GameState::SelectAction);
-            }
-            _ => (),
-        }
+    // Go to the reveal after the countdown
+    if countdown.timer.just_finished() && *current_ui_flow.get() == UiState::Countdown {
+        next_ui_flow.set(UiState::None);
+        countdown.reset(Timer::from_seconds(rules.reveal_time, TimerMode::Once));
+        next_game_flow.set(GameState::SelectAction);
     }
 }
 
 fn handle_input(
     current_ui_flow: Res<State<UiState>>,
     input: Res<ButtonInput<KeyCode>>,
+    pads: Query<&Gamepad>,
+    assignments: Res<PadAssignments>,
     mut game_data: ResMut<GameData>,
     mut writer: EventWriter<SelectElementEvent>,
+    time: Res<Time>,
 ) {
     if *current_ui_flow.get() != UiState::Countdown {
         return;
     }
-    process_input(&mut game_data.player_one, &input, Player::One, &mut writer);
-    process_input(&mut game_data.player_two, &input, Player::Two, &mut writer);
+    let now = time.elapsed_secs_f64();
+    for player in [Player::One, Player::Two] {
+        let pad = assignments.pad(player).and_then(|pad| pads.get(pad).ok());
+        process_input(
+            game_data.player_mut(player),
+            &input,
+            pad,
+            now,
+            player,
+            &mut writer,
+        );
+    }
 }
 
 fn process_input(
     player_data: &mut PlayerData,
-    input: &Res<ButtonInput<KeyCode>>,
+    input: &ButtonInput<KeyCode>,
+    pad: Option<&Gamepad>,
+    now: f64,
     player: Player,
     writer: &mut EventWriter<SelectElementEvent>,
 ) {
-    // Get the selected choice
-    let mut selected_choice = None;
-    for (key, choice) in &player_data.input.map {
-        if input.pressed(*key) {
-            selected_choice = Some(choice);
-            break;
-        }
+    player_data.input.update(input, pad, now);
+
+    // A second press of the picked choice, or the confirm binding, locks it in
+    let pick = player_data.choice_selection.element;
+    let repeated = player_data
+        .input
+        .just_pressed(input, pad)
+        .is_some_and(|choice| choice.element == pick);
+    if repeated || player_data.input.confirm_pressed(input, pad) {
+        player_data.lock(pick);
     }
 
-    if let Some(choice) = selected_choice {
+    // Get the selected choice
+    if let Some((choice, pressed_at)) = player_data.input.held() {
+        if !player_data.locked && player_data.choice_selection.element != choice.element {
+            info!(
+                "{:?} picked {} (pressed at {:.3}s)",
+                player, choice.element, pressed_at
+            );
+        }
         player_data.select_element(player, choice.element, writer);
     }
 }
@@ -172,13 +210,14 @@ fn select_audio(
     popup_query: Query<Entity, With<SelectElementPopup>>,
     mut reader: EventReader<SelectElementEvent>,
     audio_assets: Res<AudioAssets>,
+    mut rng: ResMut<CosmeticRng>,
 ) {
     for event in reader.read() {
         let audio = match event.element {
             Choice::Element(Element::Fire) => audio_assets.select_fire.clone(),
             Choice::Element(Element::Water) => audio_assets.select_water.clone(),
             Choice::Element(Element::Grass) => audio_assets.select_grass.clone(),
-            _ => get_random(&audio_assets.select_generic).clone(),
+            _ => get_random(&audio_assets.select_generic, &mut *rng).clone(),
         };
         if let Ok(popup) = popup_query.get_single() {
             commands.entity(popup).with_child(AudioPlayer::new(audio));
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    combo::GameData,
+    events::{
+        ApplyEffectsEvent, ForfeitEvent, SelectActionEvent, SelectElementEvent, TimeoutEvent,
+    },
+    online::OnlineSession,
+    replay::ReplayPlayback,
+    rng::GameRng,
+    rules::{MatchRules, Ruleset, TimeoutPolicy},
+    state::{GameState, UiState},
+    types::{Choice, Player},
+};
+
+use super::countdown::Countdown;
+
+// What a player who let the countdown run out ends up with, nothing under AutoLoss
+pub fn fill_pick(
+    policy: TimeoutPolicy,
+    selecting_element: bool,
+    last_pick: Choice,
+    ruleset: &Ruleset,
+    rng: &mut impl Rng,
+) -> Choice {
+    match (policy, selecting_element) {
+        (TimeoutPolicy::AutoLoss, _) => Choice::None,
+        (TimeoutPolicy::RepeatLast, _) if last_pick != Choice::None => last_pick,
+        (_, true) => Choice::Element(ruleset.random_element(rng)),
+        (_, false) => Choice::Action(ruleset.random_action(rng)),
+    }
+}
+
+// Runs after the countdown has been ticked, so the picks are settled before the reveal
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_timeouts(
+    countdown: Res<Countdown>,
+    current_ui_flow: Res<State<UiState>>,
+    current_game_flow: Res<State<GameState>>,
+    rules: Res<MatchRules>,
+    playback: Option<Res<ReplayPlayback>>,
+    online: Option<Res<OnlineSession>>,
+    mut game_data: ResMut<GameData>,
+    mut rng: ResMut<GameRng>,
+    mut timeout_writer: EventWriter<TimeoutEvent>,
+    mut forfeit_writer: EventWriter<ForfeitEvent>,
+    mut element_writer: EventWriter<SelectElementEvent>,
+    mut action_writer: EventWriter<SelectActionEvent>,
+    mut effects_writer: EventWriter<ApplyEffectsEvent>,
+) {
+    if !countdown.timer.just_finished() || *current_ui_flow.get() != UiState::Countdown {
+        return;
+    }
+    let state = *current_game_flow.get();
+    let selecting_element = match state {
+        GameState::SelectElement => true,
+        GameState::SelectAction => false,
+        _ => return,
+    };
+
+    for player in [Player::One, Player::Two] {
+        if online
+            .as_ref()
+            .is_some_and(|online| online.forfeited(player))
+        {
+            warn!(
+                "{:?} forfeits, their reveal didn't match their commitment",
+                player
+            );
+            game_data.state.forfeit(player);
+            forfeit_writer.send(ForfeitEvent { player });
+            continue;
+        }
+        let player_data = game_data.player_mut(player);
+        let (pick, last_pick) = if selecting_element {
+            (
+                player_data.choice_selection.element,
+                player_data.last_selection.element,
+            )
+        } else {
+            (
+                player_data.choice_selection.action,
+                player_data.last_selection.action,
+            )
+        };
+        let timed_out = match (&playback, &online) {
+            (Some(playback), _) => playback.timed_out(player, state),
+            (None, Some(online)) => online.timed_out(player).unwrap_or(pick == Choice::None),
+            (None, None) => pick == Choice::None,
+        };
+
+        if !timed_out {
+            if selecting_element {
+                player_data.last_selection.element = pick;
+            } else {
+                player_data.last_selection.action = pick;
+            }
+            continue;
+        }
+        if selecting_element {
+            player_data.element_timed_out = true;
+        } else {
+            player_data.action_timed_out = true;
+        }
+        info!("{:?} timed out", player);
+        timeout_writer.send(TimeoutEvent { player });
+
+        let policy = rules.timeout;
+        if policy == TimeoutPolicy::ForfeitHeart {
+            game_data.state.forfeit_heart(player);
+            effects_writer.send(ApplyEffectsEvent);
+        }
+        // Replays already hold the picks that were filled in
+        if pick != Choice::None || policy == TimeoutPolicy::AutoLoss {
+            continue;
+        }
+
+        let ruleset = &game_data.state.ruleset;
+        // Online fills come from both players' nonces, so both games fill the same way
+        let fill = match &online {
+            Some(online) => fill_pick(
+                policy,
+                selecting_element,
+                last_pick,
+                ruleset,
+                &mut online.fill_rng(player),
+            ),
+            None => fill_pick(policy, selecting_element, last_pick, ruleset, &mut *rng),
+        };
+        let player_data = game_data.player_mut(player);
+        if selecting_element {
+            player_data.select_element(player, fill, &mut element_writer);
+        } else {
+            player_data.select_action(player, fill, &mut action_writer);
+        }
+    }
+}
+
+// A forfeit that decides the round skips the rest of it, straight to settling the result
+pub fn skip_decided(
+    countdown: Res<Countdown>,
+    current_ui_flow: Res<State<UiState>>,
+    game_data: Res<GameData>,
+    mut next_game_flow: ResMut<NextState<GameState>>,
+) {
+    if !countdown.timer.just_finished() || *current_ui_flow.get() != UiState::Countdown {
+        return;
+    }
+    if game_data.state.decided() {
+        next_game_flow.set(GameState::ResolveAction);
+    }
+}
@@ -0,0 +1,201 @@
+use bevy::{input::gamepad::GamepadConnectionEvent, prelude::*, ui::UiSystem};
+
+use crate::{
+    config::{HOVERED_BUTTON, NORMAL_BUTTON},
+    state::GameState,
+    types::Player,
+};
+
+// Which controller plays which side. Controllers join by pressing any button
+// on the title screen, Red first, then Blue.
+#[derive(Resource, Debug, Default)]
+pub struct PadAssignments {
+    pub player_one: Option<Entity>,
+    pub player_two: Option<Entity>,
+}
+
+impl PadAssignments {
+    pub fn pad(&self, player: Player) -> Option<Entity> {
+        match player {
+            Player::One => self.player_one,
+            Player::Two => self.player_two,
+        }
+    }
+
+    fn player_for(&self, pad: Entity) -> Option<Player> {
+        [Player::One, Player::Two]
+            .into_iter()
+            .find(|player| self.pad(*player) == Some(pad))
+    }
+}
+
+// Paused while a screen wants raw controller input, such as when rebinding
+#[derive(Resource, Debug, Default)]
+pub struct PadNavigation {
+    pub paused: bool,
+}
+
+// The menu button a controller is pointing at
+#[derive(Resource, Debug, Default)]
+struct PadFocus(Option<Entity>);
+
+// A button pressed from a controller, released again on the next frame
+#[derive(Component, Debug)]
+struct PadPressed;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PadAssignments>();
+        app.init_resource::<PadFocus>();
+        app.init_resource::<PadNavigation>();
+        app.add_systems(
+            Update,
+            (
+                join_pads.run_if(in_state(GameState::Title)),
+                drop_disconnected_pads,
+            ),
+        );
+        app.add_systems(
+            PreUpdate,
+            (release_pad_presses, navigate_menus)
+                .chain()
+                .after(UiSystem::Focus)
+                .run_if(not(in_state(GameState::SelectElement)))
+                .run_if(not(in_state(GameState::SelectAction)))
+                .run_if(|navigation: Res<PadNavigation>| !navigation.paused),
+        );
+        app.add_systems(PostUpdate, highlight_focus);
+    }
+}
+
+fn join_pads(pads: Query<(Entity, &Gamepad)>, mut assignments: ResMut<PadAssignments>) {
+    for (entity, pad) in &pads {
+        if pad.get_just_pressed().next().is_none() || assignments.player_for(entity).is_some() {
+            continue;
+        }
+        if assignments.player_one.is_none() {
+            info!("Controller {} joined as Red", entity);
+            assignments.player_one = Some(entity);
+        } else if assignments.player_two.is_none() {
+            info!("Controller {} joined as Blue", entity);
+            assignments.player_two = Some(entity);
+        }
+    }
+}
+
+fn drop_disconnected_pads(
+    mut reader: EventReader<GamepadConnectionEvent>,
+    mut assignments: ResMut<PadAssignments>,
+) {
+    for event in reader.read() {
+        if event.disconnected() {
+            match assignments.player_for(event.gamepad) {
+                Some(Player::One) => assignments.player_one = None,
+                Some(Player::Two) => assignments.player_two = None,
+                None => (),
+            }
+        }
+    }
+}
+
+fn release_pad_presses(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Interaction), With<PadPressed>>,
+) {
+    for (entity, mut interaction) in &mut query {
+        *interaction = Interaction::None;
+        commands.entity(entity).remove::<PadPressed>();
+    }
+}
+
+// The d-pad moves through the visible buttons from top to bottom, and South
+// presses. Only controllers that have joined can drive the menus.
+#[allow(clippy::type_complexity)]
+fn navigate_menus(
+    mut commands: Commands,
+    pads: Query<(Entity, &Gamepad)>,
+    assignments: Res<PadAssignments>,
+    mut focus: ResMut<PadFocus>,
+    mut buttons: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &mut Interaction,
+            &mut BorderColor,
+            &mut BackgroundColor,
+        ),
+        With<Button>,
+    >,
+) {
+    let mut step = 0;
+    let mut confirm = false;
+    for (_, pad) in pads
+        .iter()
+        .filter(|(entity, _)| assignments.player_for(*entity).is_some())
+    {
+        if pad.just_pressed(GamepadButton::DPadDown) || pad.just_pressed(GamepadButton::DPadRight) {
+            step += 1;
+        }
+        if pad.just_pressed(GamepadButton::DPadUp) || pad.just_pressed(GamepadButton::DPadLeft) {
+            step -= 1;
+        }
+        confirm |= pad.just_pressed(GamepadButton::South);
+    }
+    if step == 0 && !confirm {
+        return;
+    }
+
+    let mut order: Vec<(Entity, Vec2)> = buttons
+        .iter()
+        .filter(|(_, _, visibility, ..)| visibility.get())
+        .map(|(entity, transform, ..)| (entity, transform.translation().truncate()))
+        .collect();
+    order.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    if order.is_empty() {
+        focus.0 = None;
+        return;
+    }
+
+    let current = focus
+        .0
+        .and_then(|focused| order.iter().position(|(entity, _)| *entity == focused));
+    let Some(current) = current else {
+        // The first press only picks a button to start from
+        focus.0 = Some(order[0].0);
+        return;
+    };
+    if confirm {
+        if let Ok((entity, _, _, mut interaction, ..)) = buttons.get_mut(order[current].0) {
+            *interaction = Interaction::Pressed;
+            commands.entity(entity).insert(PadPressed);
+        }
+    }
+    if step != 0 {
+        let next = (current as i32 + step).rem_euclid(order.len() as i32) as usize;
+        if let Ok((_, _, _, _, mut border, mut background)) = buttons.get_mut(order[current].0) {
+            border.0 = Color::BLACK;
+            background.0 = NORMAL_BUTTON;
+        }
+        focus.0 = Some(order[next].0);
+    }
+}
+
+fn highlight_focus(
+    focus: Res<PadFocus>,
+    mut buttons: Query<(&mut BorderColor, &mut BackgroundColor), With<Button>>,
+) {
+    let Some(focused) = focus.0 else {
+        return;
+    };
+    if let Ok((mut border, mut background)) = buttons.get_mut(focused) {
+        if border.0 != Color::WHITE {
+            border.0 = Color::WHITE;
+        }
+        if background.0 == NORMAL_BUTTON {
+            background.0 = HOVERED_BUTTON;
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use crate::{
-    combo::ResolveResult,
+    rules::ResolveResult,
     schedule::GameSet,
     types::{Action, Choice, Element, Outcome},
 };
@@ -36,6 +36,8 @@ pub struct PlayerAsset {
     pub fire: Handle<Image>,
     pub grass: Handle<Image>,
     pub water: Handle<Image>,
+    pub metal: Handle<Image>,
+    pub earth: Handle<Image>,
 }
 
 #[derive(Resource, Debug, Default)]
@@ -78,22 +80,26 @@ pub struct UiAssets {
     pub element_fire: Handle<Image>,
     pub element_grass: Handle<Image>,
     pub element_water: Handle<Image>,
+    pub element_metal: Handle<Image>,
+    pub element_earth: Handle<Image>,
     pub tool_hand: Handle<Image>,
     pub tool_toilet: Handle<Image>,
     pub tool_underwear: Handle<Image>,
+    pub tool_finger: Handle<Image>,
+    pub tool_elbow: Handle<Image>,
     pub result_swirly_p1: Handle<Image>,
     pub result_swirly_p2: Handle<Image>,
     pub result_whirly_p1: Handle<Image>,
     pub result_whirly_p2: Handle<Image>,
     pub result_wedgie_p1: Handle<Image>,
     pub result_wedgie_p2: Handle<Image>,
+    pub result_willy_p1: Handle<Image>,
+    pub result_willy_p2: Handle<Image>,
+    pub result_elbow_p1: Handle<Image>,
+    pub result_elbow_p2: Handle<Image>,
     pub chart_actions: Handle<Image>,
     pub chart_combos: Handle<Image>,
     pub chart_elements: Handle<Image>,
-    pub controls_actions_p1: Handle<Image>,
-    pub controls_actions_p2: Handle<Image>,
-    pub controls_elements_p1: Handle<Image>,
-    pub controls_elements_p2: Handle<Image>,
     pub result_draw: Handle<Image>,
     pub fira_sans_bold: Handle<Font>,
     pub ms_pain: Handle<Font>,
@@ -106,9 +112,13 @@ impl UiAssets {
             Choice::Action(Action::Hand) => self.tool_hand.clone(),
             Choice::Action(Action::Toilet) => self.tool_toilet.clone(),
             Choice::Action(Action::Underwear) => self.tool_underwear.clone(),
+            Choice::Action(Action::Finger) => self.tool_finger.clone(),
+            Choice::Action(Action::Elbow) => self.tool_elbow.clone(),
             Choice::Element(Element::Fire) => self.element_fire.clone(),
             Choice::Element(Element::Water) => self.element_water.clone(),
             Choice::Element(Element::Grass) => self.element_grass.clone(),
+            Choice::Element(Element::Metal) => self.element_metal.clone(),
+            Choice::Element(Element::Earth) => self.element_earth.clone(),
         }
     }
 
@@ -119,11 +129,15 @@ impl UiAssets {
             (Outcome::PlayerOne, Choice::Action(Action::Underwear)) => {
                 self.result_wedgie_p1.clone()
             }
+            (Outcome::PlayerOne, Choice::Action(Action::Finger)) => self.result_willy_p1.clone(),
+            (Outcome::PlayerOne, Choice::Action(Action::Elbow)) => self.result_elbow_p1.clone(),
             (Outcome::PlayerTwo, Choice::Action(Action::Hand)) => self.result_whirly_p2.clone(),
             (Outcome::PlayerTwo, Choice::Action(Action::Toilet)) => self.result_swirly_p2.clone(),
             (Outcome::PlayerTwo, Choice::Action(Action::Underwear)) => {
                 self.result_wedgie_p2.clone()
             }
+            (Outcome::PlayerTwo, Choice::Action(Action::Finger)) => self.result_willy_p2.clone(),
+            (Outcome::PlayerTwo, Choice::Action(Action::Elbow)) => self.result_elbow_p2.clone(),
             _ => self.result_draw.clone(),
         }
     }
@@ -138,9 +152,13 @@ fn setup_ui_assets(asset_server: Res<AssetServer>, mut ui_assets: ResMut<UiAsset
     ui_assets.element_fire = asset_server.load("ui/element_fire.png");
     ui_assets.element_grass = asset_server.load("ui/element_grass.png");
     ui_assets.element_water = asset_server.load("ui/element_water.png");
+    ui_assets.element_metal = asset_server.load("ui/element_metal.png");
+    ui_assets.element_earth = asset_server.load("ui/element_earth.png");
     ui_assets.tool_toilet = asset_server.load("ui/tool_toilet.png");
     ui_assets.tool_hand = asset_server.load("ui/tool_hand.png");
     ui_assets.tool_underwear = asset_server.load("ui/tool_underwear.png");
+    ui_assets.tool_finger = asset_server.load("ui/tool_finger.png");
+    ui_assets.tool_elbow = asset_server.load("ui/tool_elbow.png");
     ui_assets.fira_sans_bold = asset_server.load("fonts/FiraSans-Bold.ttf");
     ui_assets.ms_pain = asset_server.load("fonts/MS_PAIN.ttf");
     ui_assets.result_swirly_p1 = asset_server.load("ui/result_swirly_p1.png");
@@ -149,14 +167,14 @@ fn setup_ui_assets(asset_server: Res<AssetServer>, mut ui_assets: ResMut<UiAsset
     ui_assets.result_wedgie_p2 = asset_server.load("ui/result_wedgie_p2.png");
     ui_assets.result_whirly_p1 = asset_server.load("ui/result_whirly_p1.png");
     ui_assets.result_whirly_p2 = asset_server.load("ui/result_whirly_p1.png");
+    ui_assets.result_willy_p1 = asset_server.load("ui/result_willy_p1.png");
+    ui_assets.result_willy_p2 = asset_server.load("ui/result_willy_p2.png");
+    ui_assets.result_elbow_p1 = asset_server.load("ui/result_elbow_p1.png");
+    ui_assets.result_elbow_p2 = asset_server.load("ui/result_elbow_p2.png");
     ui_assets.result_draw = asset_server.load("ui/result_draw.png");
     ui_assets.chart_combos = asset_server.load("ui/chart_combos.png");
     ui_assets.chart_actions = asset_server.load("ui/chart_actions.png");
     ui_assets.chart_elements = asset_server.load("ui/chart_elements.png");
-    ui_assets.controls_actions_p1 = asset_server.load("ui/controls_actions_p1.png");
-    ui_assets.controls_actions_p2 = asset_server.load("ui/controls_actions_p2.png");
-    ui_assets.controls_elements_p1 = asset_server.load("ui/controls_elements_p1.png");
-    ui_assets.controls_elements_p2 = asset_server.load("ui/controls_elements_p2.png");
 }
 
 fn setup_game_assets(asset_server: Res<AssetServer>, mut game_assets: ResMut<GameAssets>) {
@@ -165,12 +183,16 @@ fn setup_game_assets(asset_server: Res<AssetServer>, mut game_assets: ResMut<Gam
         fire: asset_server.load("sprites/sprite_red_fire.png"),
         water: asset_server.load("sprites/sprite_red_water.png"),
         grass: asset_server.load("sprites/sprite_red_grass.png"),
+        metal: asset_server.load("sprites/sprite_red_metal.png"),
+        earth: asset_server.load("sprites/sprite_red_earth.png"),
     };
     game_assets.player_two = PlayerAsset {
         neutral: asset_server.load("sprites/sprite_blue.png"),
         fire: asset_server.load("sprites/sprite_blue_fire.png"),
         water: asset_server.load("sprites/sprite_blue_water.png"),
         grass: asset_server.load("sprites/sprite_blue_grass.png"),
+        metal: asset_server.load("sprites/sprite_blue_metal.png"),
+        earth: asset_server.load("sprites/sprite_blue_earth.png"),
     };
 }
 
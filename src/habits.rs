@@ -0,0 +1,316 @@
+// Per-match model of a player's habits, which the adaptive bot uses to counter-pick
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::rules::Ruleset;
+use crate::types::{Action, Choice, Element, Outcome, Player};
+
+// Picks seen before the model trusts a context over the broader stats
+const MIN_SAMPLES: u32 = 3;
+
+// What just happened to the player before they picked
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Context {
+    #[default]
+    Neutral,
+    AfterWin,
+    AfterLoss,
+    HoldingAdvantage,
+}
+
+#[derive(Debug)]
+struct Stats<T> {
+    // How often each pick was made
+    frequency: HashMap<T, u32>,
+    // How often each pick followed the previous one
+    transitions: HashMap<(T, T), u32>,
+}
+
+impl<T> Default for Stats<T> {
+    fn default() -> Self {
+        Self {
+            frequency: HashMap::new(),
+            transitions: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Stats<T> {
+    fn observe(&mut self, last: Option<T>, pick: T) {
+        *self.frequency.entry(pick).or_default() += 1;
+        if let Some(last) = last {
+            *self.transitions.entry((last, pick)).or_default() += 1;
+        }
+    }
+
+    fn following(&self, last: Option<T>, options: &[T]) -> Option<Vec<u32>> {
+        let last = last?;
+        let counts: Vec<u32> = options
+            .iter()
+            .map(|pick| self.transitions.get(&(last, *pick)).copied().unwrap_or(0))
+            .collect();
+        (counts.iter().sum::<u32>() >= MIN_SAMPLES).then_some(counts)
+    }
+
+    fn overall(&self, options: &[T], min_samples: u32) -> Option<Vec<u32>> {
+        let counts: Vec<u32> = options
+            .iter()
+            .map(|pick| self.frequency.get(pick).copied().unwrap_or(0))
+            .collect();
+        (counts.iter().sum::<u32>() >= min_samples).then_some(counts)
+    }
+}
+
+#[derive(Debug)]
+pub struct Habits<T> {
+    by_context: HashMap<Context, Stats<T>>,
+    all: Stats<T>,
+    last: Option<T>,
+}
+
+impl<T> Default for Habits<T> {
+    fn default() -> Self {
+        Self {
+            by_context: HashMap::new(),
+            all: Stats::default(),
+            last: None,
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Habits<T> {
+    pub fn observe(&mut self, context: Context, pick: T) {
+        self.by_context
+            .entry(context)
+            .or_default()
+            .observe(self.last, pick);
+        self.all.observe(self.last, pick);
+        self.last = Some(pick);
+    }
+
+    // Chance of each option being picked next, using the most specific stats
+    // that have enough samples: what followed the last pick in this context,
+    // then picks in this context, then what followed the last pick at all
+    pub fn predict(&self, context: Context, options: &[T]) -> Vec<f32> {
+        let context_stats = self.by_context.get(&context);
+        let counts = context_stats
+            .and_then(|stats| stats.following(self.last, options))
+            .or_else(|| context_stats.and_then(|stats| stats.overall(options, MIN_SAMPLES)))
+            .or_else(|| self.all.following(self.last, options))
+            .or_else(|| self.all.overall(options, 1))
+            .unwrap_or_else(|| vec![0; options.len()]);
+
+        // Smooth so nothing is ever ruled out completely
+        let total = counts.iter().sum::<u32>() as f32 + options.len() as f32;
+        counts
+            .iter()
+            .map(|count| (*count as f32 + 1.0) / total)
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HabitModel {
+    pub elements: Habits<Element>,
+    pub actions: Habits<Action>,
+    pub context: Context,
+}
+
+impl HabitModel {
+    pub fn observe_element(&mut self, element: Element) {
+        self.elements.observe(self.context, element);
+    }
+
+    // Record the player's action, then work out the context for their next pick
+    pub fn observe_exchange(
+        &mut self,
+        player: Player,
+        action: Action,
+        outcome: Outcome,
+        holds_advantage: bool,
+    ) {
+        self.actions.observe(self.context, action);
+        self.context = match (outcome, player) {
+            _ if holds_advantage => Context::HoldingAdvantage,
+            (Outcome::PlayerOne, Player::One) | (Outcome::PlayerTwo, Player::Two) => {
+                Context::AfterWin
+            }
+            (Outcome::PlayerOne, Player::Two) | (Outcome::PlayerTwo, Player::One) => {
+                Context::AfterLoss
+            }
+            (Outcome::Draw, _) => Context::Neutral,
+        };
+    }
+    // Before elements are revealed, expect the player to lean towards the
+    // action doubled by the element they are likely to pick
+    pub fn predict_opening(&self, ruleset: &Ruleset, actions: &[Action]) -> Vec<f32> {
+        let elements = ruleset.elements();
+        let element_odds = self.elements.predict(self.context, &elements);
+        let action_odds = self.actions.predict(self.context, actions);
+        actions
+            .iter()
+            .zip(action_odds)
+            .map(|(action, odds)| {
+                let via_element: f32 = elements
+                    .iter()
+                    .zip(&element_odds)
+                    .filter(|(element, _)| {
+                        ruleset.get_complement(Choice::Element(**element))
+                            == Choice::Action(*action)
+                    })
+                    .map(|(_, odds)| odds)
+                    .sum();
+                (odds + via_element) / 2.0
+            })
+            .collect()
+    }
+
+    // The action that does best against the player's likely next action
+    pub fn counter_action(&self, ruleset: &Ruleset) -> Option<Action> {
+        let actions = ruleset.actions();
+        let predicted = self.actions.predict(self.context, &actions);
+        best_counter(ruleset, &actions, &predicted)
+    }
+
+    // The element that doubles the best counter to the player's likely opening
+    pub fn counter_element(&self, ruleset: &Ruleset) -> Option<Element> {
+        let actions = ruleset.actions();
+        let predicted = self.predict_opening(ruleset, &actions);
+        let score = |element: Element| match ruleset.get_complement(Choice::Element(element)) {
+            Choice::Action(action) => expected_score(ruleset, action, &actions, &predicted),
+            _ => f32::MIN,
+        };
+        ruleset
+            .elements()
+            .into_iter()
+            .max_by(|a, b| score(*a).partial_cmp(&score(*b)).unwrap_or(Ordering::Equal))
+    }
+}
+
+// Chance of winning minus chance of losing against a predicted spread of actions
+fn expected_score(ruleset: &Ruleset, action: Action, actions: &[Action], predicted: &[f32]) -> f32 {
+    actions
+        .iter()
+        .zip(predicted)
+        .map(|(other, odds)| {
+            match ruleset.compare(Choice::Action(action), Choice::Action(*other)) {
+                Ordering::Greater => *odds,
+                Ordering::Less => -*odds,
+                Ordering::Equal => 0.0,
+            }
+        })
+        .sum()
+}
+
+fn best_counter(ruleset: &Ruleset, actions: &[Action], predicted: &[f32]) -> Option<Action> {
+    actions.iter().copied().max_by(|a, b| {
+        expected_score(ruleset, *a, actions, predicted)
+            .partial_cmp(&expected_score(ruleset, *b, actions, predicted))
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(actions: &[Action]) -> HabitModel {
+        let mut model = HabitModel::default();
+        for action in actions {
+            model.observe_exchange(Player::One, *action, Outcome::Draw, false);
+        }
+        model
+    }
+
+    #[test]
+    fn unseen_player_is_uniform() {
+        let actions = Ruleset::standard().actions();
+        let odds = HabitModel::default()
+            .actions
+            .predict(Context::Neutral, &actions);
+        assert!(odds.iter().all(|odds| (odds - 1.0 / 3.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn nothing_is_ruled_out() {
+        let actions = Ruleset::standard().actions();
+        let model = after(&[Action::Toilet; 6]);
+        let odds = model.actions.predict(model.context, &actions);
+        assert!(odds.iter().all(|odds| *odds > 0.0));
+        assert!((odds.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn counters_a_favourite_pick() {
+        let ruleset = Ruleset::standard();
+        let model = after(&[Action::Toilet; 4]);
+        assert_eq!(model.counter_action(&ruleset), Some(Action::Underwear));
+        // Grass doubles Underwear
+        assert_eq!(model.counter_element(&ruleset), Some(Element::Grass));
+    }
+
+    #[test]
+    fn follows_the_last_pick() {
+        let ruleset = Ruleset::standard();
+        // Toilet is always followed by Hand, so expect Hand even though
+        // both were picked equally often
+        let model = after(&[
+            Action::Toilet,
+            Action::Hand,
+            Action::Toilet,
+            Action::Hand,
+            Action::Toilet,
+            Action::Hand,
+            Action::Toilet,
+        ]);
+        assert_eq!(model.counter_action(&ruleset), Some(Action::Toilet));
+    }
+
+    #[test]
+    fn separates_contexts() {
+        let ruleset = Ruleset::standard();
+        let mut model = HabitModel::default();
+        // Hand after every win, Underwear after every loss
+        for _ in 0..4 {
+            model.context = Context::AfterWin;
+            model.observe_exchange(Player::Two, Action::Hand, Outcome::PlayerOne, false);
+            model.context = Context::AfterLoss;
+            model.observe_exchange(Player::Two, Action::Underwear, Outcome::PlayerTwo, false);
+        }
+        model.context = Context::AfterWin;
+        assert_eq!(model.counter_action(&ruleset), Some(Action::Toilet));
+        model.context = Context::AfterLoss;
+        assert_eq!(model.counter_action(&ruleset), Some(Action::Hand));
+    }
+
+    #[test]
+    fn tracks_the_context_after_an_exchange() {
+        let mut model = HabitModel::default();
+        model.observe_exchange(Player::One, Action::Hand, Outcome::PlayerOne, false);
+        assert_eq!(model.context, Context::AfterWin);
+        model.observe_exchange(Player::Two, Action::Hand, Outcome::PlayerOne, false);
+        assert_eq!(model.context, Context::AfterLoss);
+        model.observe_exchange(Player::Two, Action::Hand, Outcome::PlayerTwo, true);
+        assert_eq!(model.context, Context::HoldingAdvantage);
+        model.observe_exchange(Player::Two, Action::Hand, Outcome::Draw, false);
+        assert_eq!(model.context, Context::Neutral);
+    }
+
+    #[test]
+    fn opening_leans_towards_the_likely_element() {
+        let ruleset = Ruleset::standard();
+        let mut model = HabitModel::default();
+        for _ in 0..4 {
+            model.observe_element(Element::Fire);
+        }
+        let actions = ruleset.actions();
+        let odds = model.predict_opening(&ruleset, &actions);
+        let hand = actions.iter().position(|a| *a == Action::Hand).unwrap();
+        // Fire doubles Hand
+        assert!(odds
+            .iter()
+            .enumerate()
+            .all(|(i, other)| i == hand || *other < odds[hand]));
+    }
+}
@@ -1,12 +1,15 @@
 use bevy::prelude::*;
 use rand::seq::SliceRandom;
-use rand::thread_rng; // 0.7.2
+use rand::Rng;
 
-use crate::config::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+use crate::config::{
+    BORDER_RADIUS, BUTTON_BORDER, BUTTON_HEIGHT, BUTTON_WIDTH, HOVERED_BUTTON, NORMAL_BUTTON,
+    PRESSED_BUTTON, SIZE_M,
+};
+use crate::globals::UiAssets;
 
-pub fn get_random<T>(vec: &Vec<T>) -> &T {
-    let mut rng = thread_rng();
-    vec.choose(&mut rng).unwrap()
+pub fn get_random<'a, T>(vec: &'a [T], rng: &mut impl Rng) -> &'a T {
+    vec.choose(rng).unwrap()
 }
 
 // Generic system that takes a component as a parameter, and will despawn all entities with that component
@@ -28,6 +31,7 @@ pub fn hide<T: Component>(mut query: Query<&mut Visibility, With<T>>) {
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn handle_buttons(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &mut BorderColor),
@@ -51,3 +55,70 @@ pub fn handle_buttons(
         }
     }
 }
+
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    button: impl Component,
+    text: &str,
+    ui_assets: &UiAssets,
+) {
+    parent
+        .spawn((
+            button,
+            Button,
+            Node {
+                width: BUTTON_WIDTH,
+                height: BUTTON_HEIGHT,
+                border: BUTTON_BORDER,
+                // horizontally center child text
+                justify_content: JustifyContent::Center,
+                // vertically center child text
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BorderRadius::all(BORDER_RADIUS),
+            BackgroundColor(NORMAL_BUTTON),
+        ))
+        .with_child((
+            Text::new(text),
+            TextFont {
+                font: ui_assets.ms_pain.clone(),
+                font_size: SIZE_M,
+                ..default()
+            },
+            TextColor::WHITE,
+        ));
+}
+
+// Short label for a key, e.g. "A" for KeyCode::KeyA
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Semicolon => ";".to_string(),
+        KeyCode::Quote => "'".to_string(),
+        KeyCode::Comma => ",".to_string(),
+        KeyCode::Period => ".".to_string(),
+        KeyCode::Slash => "/".to_string(),
+        KeyCode::BracketLeft => "[".to_string(),
+        KeyCode::BracketRight => "]".to_string(),
+        KeyCode::Backslash => "\\".to_string(),
+        KeyCode::Minus => "-".to_string(),
+        KeyCode::Equal => "=".to_string(),
+        KeyCode::ShiftLeft => "L Shift".to_string(),
+        KeyCode::ShiftRight => "R Shift".to_string(),
+        KeyCode::ControlLeft => "L Ctrl".to_string(),
+        KeyCode::ControlRight => "R Ctrl".to_string(),
+        _ => {
+            let name = format!("{:?}", key);
+            name.strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .or_else(|| name.strip_prefix("Arrow"))
+                .map(str::to_string)
+                .or_else(|| {
+                    name.strip_prefix("Numpad")
+                        .map(|digit| format!("Num {}", digit))
+                })
+                .unwrap_or(name)
+        }
+    }
+}
@@ -0,0 +1,27 @@
+pub mod animations;
+pub mod bindings;
+pub mod camera;
+pub mod combo;
+pub mod computer;
+pub mod config;
+pub mod events;
+pub mod flow;
+pub mod gamepad;
+pub mod globals;
+pub mod habits;
+pub mod helper;
+pub mod online;
+pub mod opponents;
+pub mod personality;
+pub mod replay;
+pub mod rng;
+pub mod rules;
+pub mod ruleset;
+pub mod schedule;
+pub mod settings;
+pub mod sim;
+pub mod solver;
+pub mod state;
+pub mod strategy;
+pub mod types;
+pub mod ui;
@@ -1,33 +1,22 @@
 use bevy::{asset::AssetMetaCheck, prelude::*};
 
-mod animations;
-mod camera;
-mod combo;
-mod computer;
-mod config;
-mod events;
-mod flow;
-mod globals;
-mod helper;
-mod schedule;
-mod settings;
-mod state;
-mod types;
-mod ui;
-
 use bevy_tweening::TweeningPlugin;
-use camera::CameraPlugin;
-use combo::ComboPlugin;
-use computer::ComputerPlugin;
-use events::EventsPlugin;
-use flow::FlowPlugin;
-use globals::GlobalPlugin;
-use schedule::SchedulePlugin;
-use settings::SettingsPlugin;
-use state::StatePlugin;
-use ui::UIPlugin;
+use summer_jam::{
+    bindings::BindingsPlugin, camera::CameraPlugin, combo::ComboPlugin, computer::ComputerPlugin,
+    events::EventsPlugin, flow::FlowPlugin, gamepad::GamepadPlugin, globals::GlobalPlugin,
+    online::OnlinePlugin, opponents::OpponentsPlugin, replay::ReplayPlugin, rng::RngPlugin,
+    ruleset::RulesetPlugin, schedule::SchedulePlugin, settings::SettingsPlugin, solver,
+    state::StatePlugin, ui::UIPlugin,
+};
 
 fn main() {
+    // `solve` prints the equilibrium strategy table instead of starting the game
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("solve") {
+        solver::run_cli(args);
+        return;
+    }
+
     App::new()
         .add_plugins(
             DefaultPlugins
@@ -46,6 +35,7 @@ fn main() {
                 }),
         )
         .add_plugins(GlobalPlugin)
+        .add_plugins(RulesetPlugin)
         .add_plugins(EventsPlugin)
         .add_plugins(FlowPlugin)
         .add_plugins(CameraPlugin)
@@ -53,8 +43,14 @@ fn main() {
         .add_plugins(SchedulePlugin)
         .add_plugins(StatePlugin)
         .add_plugins(SettingsPlugin)
+        .add_plugins(RngPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(TweeningPlugin)
         .add_plugins(ComputerPlugin)
+        .add_plugins(OpponentsPlugin)
+        .add_plugins(GamepadPlugin)
+        .add_plugins(BindingsPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(OnlinePlugin)
         .run();
 }
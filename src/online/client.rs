@@ -0,0 +1,116 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+use super::protocol::{ClientMessage, ServerMessage};
+
+// The link to the relay. A background thread owns the socket, so the game only
+// ever touches the two channels.
+pub struct Connection {
+    outgoing: Sender<ClientMessage>,
+    incoming: Mutex<Receiver<ServerMessage>>,
+}
+
+impl Connection {
+    pub fn open(server: &str) -> Self {
+        let (outgoing, outbox) = mpsc::channel();
+        let (inbox, incoming) = mpsc::channel();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let server = server.to_string();
+            std::thread::spawn(move || native::run(&server, outbox, inbox));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (server, outbox);
+            let _ = inbox.send(ServerMessage::Error {
+                message: "Online play needs the desktop build".to_string(),
+            });
+        }
+        Self {
+            outgoing,
+            incoming: Mutex::new(incoming),
+        }
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        let _ = self.outgoing.send(message);
+    }
+
+    // Everything the relay sent since the last poll
+    pub fn poll(&self) -> Vec<ServerMessage> {
+        match self.incoming.lock() {
+            Ok(incoming) => incoming.try_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::io::ErrorKind;
+    use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+    use std::time::Duration;
+
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::{Error, Message};
+
+    use crate::online::protocol::{decode, encode, ClientMessage, ServerMessage};
+
+    // How long a read waits before the queued messages are sent
+    const POLL: Duration = Duration::from_millis(10);
+
+    pub fn run(server: &str, outgoing: Receiver<ClientMessage>, incoming: Sender<ServerMessage>) {
+        let fail = |message: String| {
+            let _ = incoming.send(ServerMessage::Error { message });
+        };
+        let mut socket = match tungstenite::connect(server) {
+            Ok((socket, _)) => socket,
+            Err(e) => return fail(format!("Could not reach {}: {}", server, e)),
+        };
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            let _ = stream.set_nodelay(true);
+            if let Err(e) = stream.set_read_timeout(Some(POLL)) {
+                return fail(e.to_string());
+            }
+        }
+
+        loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => match decode(&text) {
+                    Some(message) => {
+                        if incoming.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    None => return fail(format!("The relay sent {:?}", text)),
+                },
+                Ok(Message::Close(_)) => {
+                    return fail("The relay closed the connection".to_string())
+                }
+                Ok(_) => (),
+                Err(Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(e) => return fail(format!("Lost the connection: {}", e)),
+            }
+            loop {
+                match outgoing.try_recv() {
+                    Ok(message) => {
+                        let Some(text) = encode(&message) else {
+                            continue;
+                        };
+                        if let Err(e) = socket.send(Message::text(text)) {
+                            return fail(format!("Lost the connection: {}", e));
+                        }
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    // The game dropped the connection
+                    Err(TryRecvError::Disconnected) => {
+                        let _ = socket.close(None);
+                        let _ = socket.flush();
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
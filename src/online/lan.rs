@@ -0,0 +1,280 @@
+// Games on the local network. A host runs the relay itself and announces it over
+// UDP broadcast, so players can find each other without setting up a server.
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::parse_flag;
+use super::protocol::{decode, encode};
+
+// Bump whenever the protocol changes, so older games don't list newer hosts
+pub const LAN_VERSION: u32 = 2;
+pub const LAN_PORT: u16 = 9002;
+pub const LAN_ROOM: &str = "lan";
+const ANNOUNCE_EVERY: Duration = Duration::from_secs(1);
+// A host that stops announcing is dropped from the list after this long
+const FORGET_AFTER: Duration = Duration::from_secs(3);
+const LOOPBACK_BROADCAST: Ipv4Addr = Ipv4Addr::new(127, 255, 255, 255);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Announce {
+    version: u32,
+    name: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanGame {
+    pub name: String,
+    pub server: String,
+    port: u16,
+}
+
+// Who is hosting, as shown in other players' lists. Chosen with `--name <name>`,
+// otherwise a neutral name with a number to tell hosts apart, so nothing about
+// this machine or its user is broadcast.
+pub fn host_name() -> String {
+    let args: Vec<String> = std::env::args().collect();
+    parse_flag(&args, "--name")
+        .unwrap_or_else(|| format!("Player {}", rand::thread_rng().gen_range(100..1000)))
+}
+
+// Listens for hosts announcing themselves, present while the LAN Games menu is open
+#[derive(Resource, Debug)]
+pub struct LanBrowser {
+    socket: Option<UdpSocket>,
+    games: Vec<(LanGame, Instant)>,
+    pub error: Option<String>,
+}
+
+impl LanBrowser {
+    pub fn open() -> Self {
+        match listen() {
+            Ok(socket) => Self {
+                socket: Some(socket),
+                games: Vec::new(),
+                error: None,
+            },
+            Err(e) => Self {
+                socket: None,
+                games: Vec::new(),
+                error: Some(format!("Can't listen for LAN games: {}", e)),
+            },
+        }
+    }
+
+    // Whether the list changed since the last poll
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let mut changed = false;
+        let mut buffer = [0; 512];
+        while let Some(Ok((length, from))) = self
+            .socket
+            .as_ref()
+            .map(|socket| socket.recv_from(&mut buffer))
+        {
+            let announce = std::str::from_utf8(&buffer[..length])
+                .ok()
+                .and_then(decode::<Announce>);
+            if let Some(announce) = announce.filter(|announce| announce.version == LAN_VERSION) {
+                changed |= self.seen(announce, from, now);
+            }
+        }
+        let count = self.games.len();
+        self.games
+            .retain(|(_, seen)| now.duration_since(*seen) < FORGET_AFTER);
+        changed || self.games.len() != count
+    }
+
+    // A host is heard both over loopback and the network when it runs on this machine
+    fn seen(&mut self, announce: Announce, from: SocketAddr, now: Instant) -> bool {
+        let existing = self
+            .games
+            .iter_mut()
+            .find(|(game, _)| game.name == announce.name && game.port == announce.port);
+        if let Some((_, seen)) = existing {
+            *seen = now;
+            return false;
+        }
+        let game = LanGame {
+            server: format!("ws://{}:{}", from.ip(), announce.port),
+            name: announce.name,
+            port: announce.port,
+        };
+        self.games.push((game, now));
+        true
+    }
+
+    pub fn games(&self) -> impl Iterator<Item = &LanGame> {
+        self.games.iter().map(|(game, _)| game)
+    }
+}
+
+// Several games on one machine can have the LAN Games menu open at once, so the
+// port is shared
+#[cfg(not(target_arch = "wasm32"))]
+fn listen() -> io::Result<UdpSocket> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, LAN_PORT)).into())?;
+    socket.set_nonblocking(true)?;
+    Ok(socket.into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn listen() -> io::Result<UdpSocket> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "LAN games need the desktop build",
+    ))
+}
+
+// A game hosted from here. The relay runs until this is dropped, the announcements
+// until then or until the room fills.
+#[derive(Resource, Debug)]
+pub struct LanHost {
+    pub port: u16,
+    stop: Arc<AtomicBool>,
+    full: Arc<AtomicBool>,
+}
+
+impl LanHost {
+    pub fn start(name: String) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        let port = listener.local_addr()?.port();
+        let stop = Arc::new(AtomicBool::new(false));
+        serve(listener, stop.clone())?;
+
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_broadcast(true)?;
+        let announce = Announce {
+            version: LAN_VERSION,
+            name,
+            port,
+        };
+        let message = encode(&announce).unwrap_or_default();
+        let full = Arc::new(AtomicBool::new(false));
+        let (beacon_stop, beacon_full) = (stop.clone(), full.clone());
+        thread::spawn(move || {
+            while !beacon_stop.load(Ordering::Relaxed) && !beacon_full.load(Ordering::Relaxed) {
+                // Loopback as well, for other games on this machine. Broadcast, as only
+                // one of the browsers sharing the port would get a plain loopback message.
+                for address in [Ipv4Addr::BROADCAST, LOOPBACK_BROADCAST] {
+                    let _ = socket.send_to(message.as_bytes(), (address, LAN_PORT));
+                }
+                thread::sleep(ANNOUNCE_EVERY);
+            }
+        });
+        info!("Hosting a LAN game on port {}", port);
+        Ok(Self { port, stop, full })
+    }
+
+    // Stops announcing a game nobody else can join
+    pub fn fill(&self) {
+        self.full.store(true, Ordering::Relaxed);
+    }
+
+    // Where the host's own game connects
+    pub fn server(&self) -> String {
+        format!("ws://127.0.0.1:{}", self.port)
+    }
+}
+
+impl Drop for LanHost {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn serve(listener: TcpListener, stop: Arc<AtomicBool>) -> io::Result<()> {
+    thread::spawn(move || {
+        if let Err(e) = super::server::serve(listener, stop) {
+            warn!("LAN relay stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn serve(_listener: TcpListener, _stop: Arc<AtomicBool>) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "hosting needs the desktop build",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser() -> LanBrowser {
+        LanBrowser {
+            socket: None,
+            games: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn announce() -> Announce {
+        Announce {
+            version: LAN_VERSION,
+            name: "Player 123".to_string(),
+            port: 4000,
+        }
+    }
+
+    #[test]
+    fn a_host_heard_twice_is_listed_once() {
+        let mut browser = browser();
+        let now = Instant::now();
+        assert!(browser.seen(announce(), "127.0.0.1:5000".parse().unwrap(), now));
+        assert!(!browser.seen(announce(), "192.168.1.20:5000".parse().unwrap(), now));
+        let games: Vec<_> = browser.games().collect();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].server, "ws://127.0.0.1:4000");
+
+        let other = Announce {
+            port: 4001,
+            ..announce()
+        };
+        assert!(browser.seen(other, "192.168.1.20:5000".parse().unwrap(), now));
+        assert_eq!(browser.games().count(), 2);
+    }
+
+    #[test]
+    fn a_quiet_host_is_forgotten() {
+        let mut browser = browser();
+        let now = Instant::now();
+        browser.seen(announce(), "127.0.0.1:5000".parse().unwrap(), now);
+        let later = now + FORGET_AFTER - Duration::from_millis(1);
+        assert!(!browser.poll(later));
+        assert_eq!(browser.games().count(), 1);
+
+        // Hearing it again keeps it listed
+        browser.seen(announce(), "127.0.0.1:5000".parse().unwrap(), later);
+        assert!(!browser.poll(now + FORGET_AFTER));
+        assert_eq!(browser.games().count(), 1);
+
+        assert!(browser.poll(later + FORGET_AFTER));
+        assert_eq!(browser.games().count(), 0);
+    }
+
+    #[test]
+    fn two_browsers_share_the_port() {
+        let first = LanBrowser::open();
+        let second = LanBrowser::open();
+        assert_eq!(first.error, None);
+        assert_eq!(second.error, None);
+    }
+}
@@ -0,0 +1,436 @@
+// Online 1v1 through swirly-relay. Both games run the whole match themselves,
+// the relay pairs the players, times each countdown and passes the final picks along.
+// Picks are only sent as commitments until the countdown closes, then revealed.
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+mod client;
+pub mod lan;
+pub mod protocol;
+pub mod relay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+
+use client::Connection;
+use lan::LanHost;
+use protocol::{
+    commit, ClientMessage, Commitment, Phase, ServerMessage, DEFAULT_ROOM, DEFAULT_SERVER,
+};
+
+use crate::{
+    combo::GameData,
+    config::START_STATE,
+    events::{SelectActionEvent, SelectElementEvent},
+    flow::countdown::Countdown,
+    rules::MatchRules,
+    schedule::GameSet,
+    settings::{GameMode, GameSettings},
+    state::{GameState, UiState},
+    types::{Choice, Player},
+};
+
+// Where to play online, from `--server <url>` and `--room <name>`
+#[derive(Resource, Debug, Clone)]
+pub struct OnlineConfig {
+    pub server: String,
+    pub room: String,
+}
+
+impl OnlineConfig {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        Self {
+            server: parse_flag(&args, "--server").unwrap_or_else(|| DEFAULT_SERVER.to_string()),
+            room: parse_flag(&args, "--room").unwrap_or_else(|| DEFAULT_ROOM.to_string()),
+        }
+    }
+}
+
+// Reads `--flag <value>` or `--flag=<value>` from the command line
+fn parse_flag(args: &[String], flag: &str) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|arg| arg.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+// A player's pick once the relay has checked it against their commitment
+#[derive(Debug, Copy, Clone)]
+struct Final {
+    choice: Choice,
+    nonce: u64,
+    forfeit: bool,
+}
+
+fn slot(player: Player) -> usize {
+    match player {
+        Player::One => 0,
+        Player::Two => 1,
+    }
+}
+
+// Present from joining a room until the online match ends
+#[derive(Resource)]
+pub struct OnlineSession {
+    connection: Connection,
+    server: String,
+    room: String,
+    // Shown in the lobby
+    pub status: String,
+    side: Option<Player>,
+    seed: u64,
+    previous_settings: Option<(GameSettings, MatchRules)>,
+    phase: Phase,
+    // Every commitment made this countdown, with the pick and nonce behind it
+    commitments: Vec<(Commitment, Choice, u64)>,
+    committed: Option<Choice>,
+    lock_sent: bool,
+    // Our commitment that stood when the relay closed the countdown
+    closed: Option<Option<Commitment>>,
+    revealed: bool,
+    finals: [Option<Final>; 2],
+    applied: bool,
+    disconnected: bool,
+}
+
+impl OnlineSession {
+    pub fn connect(config: &OnlineConfig, settings: &GameSettings, rules: &MatchRules) -> Self {
+        info!("Connecting to {}", config.server);
+        let connection = Connection::open(&config.server);
+        connection.send(ClientMessage::Join {
+            room: config.room.clone(),
+            settings: settings.clone(),
+            rules: *rules,
+        });
+        Self {
+            connection,
+            server: config.server.clone(),
+            room: config.room.clone(),
+            status: format!("Connecting to {}...", config.server),
+            side: None,
+            seed: 0,
+            previous_settings: None,
+            phase: 0,
+            commitments: Vec::new(),
+            committed: None,
+            lock_sent: false,
+            closed: None,
+            revealed: false,
+            finals: [None; 2],
+            applied: false,
+            disconnected: false,
+        }
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    // Known once the relay has sent the player's final pick
+    pub fn timed_out(&self, player: Player) -> Option<bool> {
+        self.finals[slot(player)].map(|pick| pick.choice == Choice::None && !pick.forfeit)
+    }
+
+    pub fn forfeited(&self, player: Player) -> bool {
+        self.finals[slot(player)].is_some_and(|pick| pick.forfeit)
+    }
+
+    // Neither nonce is known to the other player until the reveal, so neither can steer a timeout fill
+    pub fn fill_rng(&self, player: Player) -> StdRng {
+        let nonce = |player| self.finals[slot(player)].map_or(0, |pick| pick.nonce);
+        let phase = (u64::from(self.phase) << 1) | slot(player) as u64;
+        StdRng::seed_from_u64(
+            self.seed
+                ^ nonce(Player::One)
+                ^ nonce(Player::Two).rotate_left(32)
+                ^ phase.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        )
+    }
+
+    // Stand by a new pick with a fresh nonce, replacing the last commitment
+    fn commit(&mut self, choice: Choice) {
+        let Some(side) = self.side else {
+            return;
+        };
+        let nonce = rand::random();
+        let commitment = commit(self.phase, side, choice, nonce);
+        self.commitments.push((commitment.clone(), choice, nonce));
+        self.committed = Some(choice);
+        self.connection.send(ClientMessage::Commit {
+            phase: self.phase,
+            commitment,
+        });
+    }
+}
+
+pub struct OnlinePlugin;
+
+impl Plugin for OnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(OnlineConfig::from_args());
+        app.add_systems(
+            Update,
+            receive_messages
+                .run_if(resource_exists::<OnlineSession>)
+                .in_set(GameSet::Flow),
+        );
+        app.add_systems(
+            OnEnter(UiState::Countdown),
+            start_phase
+                .run_if(resource_exists::<OnlineSession>)
+                .in_set(GameSet::Flow),
+        );
+        app.add_systems(
+            Update,
+            sync_countdown
+                .run_if(in_state(UiState::Countdown))
+                .run_if(resource_exists::<OnlineSession>)
+                .in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            OnExit(GameState::GameOver),
+            end_session.run_if(resource_exists::<OnlineSession>),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn receive_messages(
+    mut session: ResMut<OnlineSession>,
+    mut settings: ResMut<GameSettings>,
+    mut rules: ResMut<MatchRules>,
+    mut countdown: ResMut<Countdown>,
+    mut game_data: ResMut<GameData>,
+    host: Option<Res<LanHost>>,
+    current_game_flow: Res<State<GameState>>,
+    mut next_game_flow: ResMut<NextState<GameState>>,
+) {
+    for message in session.connection.poll() {
+        match message {
+            ServerMessage::Welcome { player } => {
+                info!("Joined room {} as {:?}", session.room, player);
+                session.side = Some(player);
+                session.status = format!("Waiting for an opponent in room {}", session.room);
+            }
+            ServerMessage::Start {
+                settings: host_settings,
+                rules: host_rules,
+                seed,
+            } => {
+                let Some(side) = session.side else {
+                    continue;
+                };
+                if *current_game_flow.get() != GameState::OnlineLobby {
+                    continue;
+                }
+                info!("Online match starting with seed {}", seed);
+                if let Some(host) = &host {
+                    host.fill();
+                }
+                session.seed = seed;
+                // Play the host's match, restoring our own settings once it ends
+                let previous_settings = std::mem::replace(
+                    &mut *settings,
+                    GameSettings {
+                        game_mode: GameMode::Online(side),
+                        seed: Some(seed),
+                        ..host_settings
+                    },
+                );
+                let previous_rules = std::mem::replace(&mut *rules, host_rules);
+                session.previous_settings = Some((previous_settings, previous_rules));
+                next_game_flow.set(START_STATE);
+            }
+            // Both games start the countdown on the relay's word
+            ServerMessage::Go { phase, host_state } if phase == session.phase => {
+                // Should the games ever drift apart, the joiner carries on from the host's match
+                if let Some(host_state) = host_state {
+                    if session.side == Some(Player::Two) && host_state != game_data.state {
+                        warn!(
+                            "Out of sync with the host at phase {}, using its match",
+                            phase
+                        );
+                        game_data.state = host_state;
+                    }
+                }
+                countdown.reset(Timer::from_seconds(rules.countdown_time, TimerMode::Once));
+            }
+            ServerMessage::Close { phase, commitment } if phase == session.phase => {
+                session.closed = Some(commitment);
+            }
+            ServerMessage::Final {
+                phase,
+                player,
+                choice,
+                nonce,
+                forfeit,
+            } if phase == session.phase => {
+                session.finals[slot(player)] = Some(Final {
+                    choice,
+                    nonce,
+                    forfeit,
+                });
+            }
+            ServerMessage::OpponentLeft => {
+                info!("Opponent left the match");
+                session.status = "Your opponent left".to_string();
+            }
+            ServerMessage::Error { message } => {
+                warn!("Online: {}", message);
+                session.status = message;
+                session.disconnected = true;
+            }
+            // Left over from a countdown that has already ended
+            _ => (),
+        }
+    }
+}
+
+// Every countdown waits for both players to be ready, and stays open until the relay closes it
+fn start_phase(
+    mut session: ResMut<OnlineSession>,
+    mut countdown: ResMut<Countdown>,
+    game_data: Res<GameData>,
+) {
+    session.phase += 1;
+    session.commitments.clear();
+    session.committed = None;
+    session.lock_sent = false;
+    session.closed = None;
+    session.revealed = false;
+    session.finals = [None; 2];
+    session.applied = false;
+    if session.disconnected {
+        return;
+    }
+    countdown.held = true;
+    countdown.timer.pause();
+    let phase = session.phase;
+    let state = game_data.state.clone();
+    session
+        .connection
+        .send(ClientMessage::Ready { phase, state });
+    // Even no pick is committed to, so there is a nonce to reveal if the time runs out
+    session.commit(Choice::None);
+}
+
+fn sync_countdown(
+    mut session: ResMut<OnlineSession>,
+    mut countdown: ResMut<Countdown>,
+    current_game_flow: Res<State<GameState>>,
+    mut game_data: ResMut<GameData>,
+    mut element_writer: EventWriter<SelectElementEvent>,
+    mut action_writer: EventWriter<SelectActionEvent>,
+) {
+    let Some(side) = session.side else {
+        return;
+    };
+    let selecting_element = match current_game_flow.get() {
+        GameState::SelectElement => true,
+        GameState::SelectAction => false,
+        _ => return,
+    };
+    if !countdown.held {
+        return;
+    }
+    let phase = session.phase;
+    let player_data = game_data.player(side);
+    let pick = if selecting_element {
+        player_data.choice_selection.element
+    } else {
+        player_data.choice_selection.action
+    };
+
+    if session.closed.is_none() && !session.disconnected {
+        // The relay always holds a commitment to the latest pick
+        if session.committed != Some(pick) {
+            session.commit(pick);
+        }
+        // Let the relay end the countdown early once both players are locked in
+        if player_data.locked && !session.lock_sent && !countdown.timer.paused() {
+            session.lock_sent = true;
+            session.connection.send(ClientMessage::Lock { phase });
+        }
+    }
+
+    // Open the commitment that stood when the countdown closed, which may be older than
+    // the pick on screen if the last change didn't reach the relay in time
+    if let (Some(standing), false) = (&session.closed, session.revealed) {
+        let opened = session
+            .commitments
+            .iter()
+            .find(|(commitment, ..)| Some(commitment) == standing.as_ref())
+            .map(|&(_, choice, nonce)| (choice, nonce));
+        if let Some((choice, nonce)) = opened {
+            session.connection.send(ClientMessage::Reveal {
+                phase,
+                choice,
+                nonce,
+            });
+        }
+        session.revealed = true;
+        game_data.player_mut(side).locked = true;
+    }
+
+    // Nothing more will come from the relay, so settle the countdown without it
+    if session.disconnected {
+        let local = &mut session.finals[slot(side)];
+        local.get_or_insert(Final {
+            choice: pick,
+            nonce: 0,
+            forfeit: false,
+        });
+        let remote = &mut session.finals[slot(side.opponent())];
+        remote.get_or_insert(Final {
+            choice: Choice::None,
+            nonce: 0,
+            forfeit: false,
+        });
+    }
+
+    // Both picks are in, so apply them and let the countdown run out
+    let [Some(one), Some(two)] = session.finals else {
+        return;
+    };
+    if session.applied {
+        return;
+    }
+    for (player, pick) in [(Player::One, one), (Player::Two, two)] {
+        let player_data = game_data.player_mut(player);
+        player_data.locked = false;
+        match (pick.choice, selecting_element) {
+            (Choice::None, true) => player_data.choice_selection.element = Choice::None,
+            (Choice::None, false) => player_data.choice_selection.action = Choice::None,
+            (choice, true) => player_data.select_element(player, choice, &mut element_writer),
+            (choice, false) => player_data.select_action(player, choice, &mut action_writer),
+        }
+        player_data.locked = true;
+    }
+    session.applied = true;
+    countdown.held = false;
+    countdown.timer.unpause();
+}
+
+fn end_session(
+    mut commands: Commands,
+    session: Res<OnlineSession>,
+    mut settings: ResMut<GameSettings>,
+    mut rules: ResMut<MatchRules>,
+) {
+    if let Some((previous_settings, previous_rules)) = &session.previous_settings {
+        *settings = previous_settings.clone();
+        *rules = *previous_rules;
+    }
+    commands.remove_resource::<OnlineSession>();
+    commands.remove_resource::<LanHost>();
+}
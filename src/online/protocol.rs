@@ -0,0 +1,102 @@
+// Messages between the game and swirly-relay, sent as RON text frames
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::rules::{MatchRules, MatchState};
+use crate::settings::GameSettings;
+use crate::types::{Choice, Player};
+
+pub const DEFAULT_PORT: u16 = 9001;
+pub const DEFAULT_SERVER: &str = "ws://127.0.0.1:9001";
+pub const DEFAULT_ROOM: &str = "lobby";
+
+// Every countdown is one phase, counted from 1 at the start of the match
+pub type Phase = u32;
+
+// Hex SHA-256 of a pick, its phase, side and a random nonce
+pub type Commitment = String;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    // The first player in a room hosts it, and their settings and rules are used for the match
+    Join {
+        room: String,
+        settings: GameSettings,
+        rules: MatchRules,
+    },
+    // Reached the start of a countdown, with the match as it stands in this game
+    Ready {
+        phase: Phase,
+        state: MatchState,
+    },
+    // Locked in early, so the countdown can end once both players have
+    Lock {
+        phase: Phase,
+    },
+    // Stands for the current pick until it is revealed, replacing any earlier commitment
+    Commit {
+        phase: Phase,
+        commitment: Commitment,
+    },
+    // Opens the commitment that stood when the countdown closed
+    Reveal {
+        phase: Phase,
+        choice: Choice,
+        nonce: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    // Which side this client plays
+    Welcome {
+        player: Player,
+    },
+    // Both players are in, with the host's settings, rules and the seed for the match
+    Start {
+        settings: GameSettings,
+        rules: MatchRules,
+        seed: u64,
+    },
+    // Both players are ready, the countdown starts now. The host's game is the
+    // authority on the match, so its state comes along for the joiner to check.
+    Go {
+        phase: Phase,
+        host_state: Option<MatchState>,
+    },
+    // The countdown is over on the server's clock, along with the commitment of ours that stands
+    Close {
+        phase: Phase,
+        commitment: Option<Commitment>,
+    },
+    // A player's checked pick, sent to both players once both are known. No pick is a
+    // timeout unless the reveal didn't match the commitment or never came, which forfeits the set.
+    Final {
+        phase: Phase,
+        player: Player,
+        choice: Choice,
+        nonce: u64,
+        forfeit: bool,
+    },
+    OpponentLeft,
+    Error {
+        message: String,
+    },
+}
+
+pub fn encode<T: Serialize>(message: &T) -> Option<String> {
+    ron::to_string(message).ok()
+}
+
+pub fn decode<T: DeserializeOwned>(text: &str) -> Option<T> {
+    ron::from_str(text).ok()
+}
+
+// Binds a pick to its countdown and side without giving it away until the nonce is revealed
+pub fn commit(phase: Phase, player: Player, choice: Choice, nonce: u64) -> Commitment {
+    let preimage = encode(&(phase, player, choice, nonce)).unwrap_or_default();
+    Sha256::digest(preimage.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
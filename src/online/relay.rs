@@ -0,0 +1,663 @@
+// Pairs players into rooms of two and runs every countdown on the relay's clock.
+// Both games play the match out themselves, the relay only checks the picks and passes them along.
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::rules::{MatchRules, MatchState};
+use crate::settings::GameSettings;
+use crate::types::{Choice, Player};
+
+use super::protocol::{commit, ClientMessage, Commitment, Phase, ServerMessage};
+
+pub type ClientId = u64;
+
+// How long players have to reveal once a countdown closes
+const REVEAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+// A checked pick, held back until both players have one
+#[derive(Debug, Copy, Clone)]
+struct Final {
+    choice: Choice,
+    nonce: u64,
+    forfeit: bool,
+}
+
+#[derive(Debug, Default)]
+struct Seat {
+    client: Option<ClientId>,
+    // Left after the match started, so the relay answers for them
+    left: bool,
+    ready: bool,
+    // The match as the player's game had it at the start of the countdown
+    state: Option<MatchState>,
+    locked: bool,
+    // The last commitment made before the countdown closed
+    commitment: Option<Commitment>,
+    final_pick: Option<Final>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Countdown {
+    // Waiting for both players to be ready
+    Waiting,
+    Running(Instant),
+    // Waiting for both reveals, until the deadline
+    Closed(Instant),
+    // Both final picks have been sent
+    Settled,
+}
+
+#[derive(Debug)]
+struct Room {
+    settings: GameSettings,
+    rules: MatchRules,
+    seats: [Seat; 2],
+    started: bool,
+    phase: Phase,
+    countdown: Countdown,
+}
+
+fn seat_player(index: usize) -> Player {
+    match index {
+        0 => Player::One,
+        _ => Player::Two,
+    }
+}
+
+impl Room {
+    fn new(settings: GameSettings, rules: MatchRules) -> Self {
+        Self {
+            settings,
+            rules,
+            seats: Default::default(),
+            started: false,
+            phase: 0,
+            countdown: Countdown::Waiting,
+        }
+    }
+
+    fn send(
+        &self,
+        outbox: &mut Vec<(ClientId, ServerMessage)>,
+        seat: usize,
+        message: ServerMessage,
+    ) {
+        if let Some(client) = self.seats[seat].client {
+            outbox.push((client, message));
+        }
+    }
+
+    fn broadcast(&self, outbox: &mut Vec<(ClientId, ServerMessage)>, message: ServerMessage) {
+        for seat in 0..2 {
+            self.send(outbox, seat, message.clone());
+        }
+    }
+
+    fn ready(
+        &mut self,
+        seat: usize,
+        phase: Phase,
+        state: MatchState,
+        now: Instant,
+        outbox: &mut Vec<(ClientId, ServerMessage)>,
+    ) {
+        // The first player to reach a countdown opens its phase. Phases are
+        // counted one at a time, so anything further ahead is ignored.
+        if phase == self.phase + 1 {
+            self.phase = phase;
+            self.countdown = Countdown::Waiting;
+            for seat in &mut self.seats {
+                seat.ready = false;
+                seat.state = None;
+                seat.locked = false;
+                seat.commitment = None;
+                seat.final_pick = None;
+            }
+        }
+        if phase != self.phase {
+            return;
+        }
+        self.seats[seat].ready = true;
+        self.seats[seat].state = Some(state);
+        self.start_if_ready(now, outbox);
+    }
+
+    fn start_if_ready(&mut self, now: Instant, outbox: &mut Vec<(ClientId, ServerMessage)>) {
+        if self.countdown != Countdown::Waiting
+            || self.phase == 0
+            || !self.seats.iter().all(|seat| seat.ready || seat.left)
+        {
+            return;
+        }
+        let length = Duration::from_secs_f32(self.rules.countdown_time);
+        self.countdown = Countdown::Running(now + length);
+        let message = ServerMessage::Go {
+            phase: self.phase,
+            host_state: self.seats[0].state.clone(),
+        };
+        self.broadcast(outbox, message);
+    }
+
+    fn lock(
+        &mut self,
+        seat: usize,
+        phase: Phase,
+        now: Instant,
+        outbox: &mut Vec<(ClientId, ServerMessage)>,
+    ) {
+        if phase != self.phase || !matches!(self.countdown, Countdown::Running(_)) {
+            return;
+        }
+        self.seats[seat].locked = true;
+        self.close_if_locked(now, outbox);
+    }
+
+    fn close_if_locked(&mut self, now: Instant, outbox: &mut Vec<(ClientId, ServerMessage)>) {
+        if matches!(self.countdown, Countdown::Running(_))
+            && self.seats.iter().all(|seat| seat.locked || seat.left)
+        {
+            self.close(now, outbox);
+        }
+    }
+
+    fn commit(&mut self, seat: usize, phase: Phase, commitment: Commitment) {
+        let open = matches!(self.countdown, Countdown::Waiting | Countdown::Running(_));
+        if phase == self.phase && open {
+            self.seats[seat].commitment = Some(commitment);
+        }
+    }
+
+    fn close(&mut self, now: Instant, outbox: &mut Vec<(ClientId, ServerMessage)>) {
+        self.countdown = Countdown::Closed(now + REVEAL_TIMEOUT);
+        for seat in 0..2 {
+            let message = ServerMessage::Close {
+                phase: self.phase,
+                commitment: self.seats[seat].commitment.clone(),
+            };
+            self.send(outbox, seat, message);
+        }
+        // Nothing will be revealed by a player who left or never committed
+        for seat in 0..2 {
+            if self.seats[seat].left || self.seats[seat].commitment.is_none() {
+                self.finish(seat, Choice::None, 0, false, outbox);
+            }
+        }
+    }
+
+    fn reveal(
+        &mut self,
+        seat: usize,
+        phase: Phase,
+        choice: Choice,
+        nonce: u64,
+        outbox: &mut Vec<(ClientId, ServerMessage)>,
+    ) {
+        if phase != self.phase || !matches!(self.countdown, Countdown::Closed(_)) {
+            return;
+        }
+        let opened = commit(phase, seat_player(seat), choice, nonce);
+        if self.seats[seat].commitment.as_ref() == Some(&opened) {
+            self.finish(seat, choice, nonce, false, outbox);
+        } else {
+            self.finish(seat, Choice::None, nonce, true, outbox);
+        }
+    }
+
+    // Both players get every final pick, so both games settle the exchange the same way.
+    // Neither is sent until both are known, so nobody sees a pick before they have revealed.
+    fn finish(
+        &mut self,
+        seat: usize,
+        choice: Choice,
+        nonce: u64,
+        forfeit: bool,
+        outbox: &mut Vec<(ClientId, ServerMessage)>,
+    ) {
+        if self.seats[seat].final_pick.is_some() {
+            return;
+        }
+        self.seats[seat].final_pick = Some(Final {
+            choice,
+            nonce,
+            forfeit,
+        });
+        let [Some(first), Some(second)] = self.seats.each_ref().map(|seat| seat.final_pick) else {
+            return;
+        };
+        self.countdown = Countdown::Settled;
+        for (seat, pick) in [first, second].into_iter().enumerate() {
+            let message = ServerMessage::Final {
+                phase: self.phase,
+                player: seat_player(seat),
+                choice: pick.choice,
+                nonce: pick.nonce,
+                forfeit: pick.forfeit,
+            };
+            self.broadcast(outbox, message);
+        }
+    }
+
+    fn leave(&mut self, seat: usize, now: Instant, outbox: &mut Vec<(ClientId, ServerMessage)>) {
+        self.seats[seat].client = None;
+        self.seats[seat].left = true;
+        self.send(outbox, 1 - seat, ServerMessage::OpponentLeft);
+        match self.countdown {
+            Countdown::Waiting => self.start_if_ready(now, outbox),
+            Countdown::Running(_) => self.close_if_locked(now, outbox),
+            // Leaving instead of revealing a commitment is a forfeit
+            Countdown::Closed(_) => self.finish(seat, Choice::None, 0, true, outbox),
+            Countdown::Settled => (),
+        }
+    }
+
+    fn tick(&mut self, now: Instant, outbox: &mut Vec<(ClientId, ServerMessage)>) {
+        match self.countdown {
+            Countdown::Running(deadline) if now >= deadline => self.close(now, outbox),
+            // Holding back a reveal after seeing the countdown close is a forfeit
+            Countdown::Closed(deadline) if now >= deadline => {
+                for seat in 0..2 {
+                    self.finish(seat, Choice::None, 0, true, outbox);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        match self.countdown {
+            Countdown::Running(deadline) | Countdown::Closed(deadline) => Some(deadline),
+            Countdown::Waiting | Countdown::Settled => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Relay {
+    rooms: HashMap<String, Room>,
+    clients: HashMap<ClientId, (String, usize)>,
+    outbox: Vec<(ClientId, ServerMessage)>,
+}
+
+impl Relay {
+    pub fn receive(&mut self, client: ClientId, message: ClientMessage, now: Instant) {
+        if let ClientMessage::Join {
+            room,
+            settings,
+            rules,
+        } = message
+        {
+            self.join(client, room, settings, rules);
+            return;
+        }
+        let Some((name, seat)) = self.clients.get(&client) else {
+            self.outbox.push((
+                client,
+                ServerMessage::Error {
+                    message: "Join a room first".to_string(),
+                },
+            ));
+            return;
+        };
+        let Some(room) = self.rooms.get_mut(name) else {
+            return;
+        };
+        let outbox = &mut self.outbox;
+        match message {
+            ClientMessage::Join { .. } => (),
+            ClientMessage::Ready { phase, state } => room.ready(*seat, phase, state, now, outbox),
+            ClientMessage::Lock { phase } => room.lock(*seat, phase, now, outbox),
+            ClientMessage::Commit { phase, commitment } => room.commit(*seat, phase, commitment),
+            ClientMessage::Reveal {
+                phase,
+                choice,
+                nonce,
+            } => room.reveal(*seat, phase, choice, nonce, outbox),
+        }
+    }
+
+    fn join(&mut self, client: ClientId, name: String, settings: GameSettings, rules: MatchRules) {
+        if self.clients.contains_key(&client) {
+            self.outbox.push((
+                client,
+                ServerMessage::Error {
+                    message: "Already in a room".to_string(),
+                },
+            ));
+            return;
+        }
+        // The relay times every countdown from the host's rules, so keep them playable
+        let room = self
+            .rooms
+            .entry(name.clone())
+            .or_insert_with(|| Room::new(settings, rules.clamped()));
+        let Some(seat) = (0..2).find(|&seat| room.seats[seat].client.is_none()) else {
+            self.outbox.push((
+                client,
+                ServerMessage::Error {
+                    message: format!("Room {} is full", name),
+                },
+            ));
+            return;
+        };
+        if room.started {
+            self.outbox.push((
+                client,
+                ServerMessage::Error {
+                    message: format!("Room {} is already playing", name),
+                },
+            ));
+            return;
+        }
+        room.seats[seat].client = Some(client);
+        self.clients.insert(client, (name, seat));
+        self.outbox.push((
+            client,
+            ServerMessage::Welcome {
+                player: seat_player(seat),
+            },
+        ));
+
+        if room.seats.iter().all(|seat| seat.client.is_some()) {
+            room.started = true;
+            let seed = room.settings.seed.unwrap_or_else(rand::random);
+            let message = ServerMessage::Start {
+                settings: room.settings.clone(),
+                rules: room.rules,
+                seed,
+            };
+            room.broadcast(&mut self.outbox, message);
+        }
+    }
+
+    pub fn leave(&mut self, client: ClientId, now: Instant) {
+        let Some((name, seat)) = self.clients.remove(&client) else {
+            return;
+        };
+        let Some(room) = self.rooms.get_mut(&name) else {
+            return;
+        };
+        if !room.started {
+            self.rooms.remove(&name);
+            return;
+        }
+        room.leave(seat, now, &mut self.outbox);
+        if room.seats.iter().all(|seat| seat.left) {
+            self.rooms.remove(&name);
+        }
+    }
+
+    // Closes every countdown that has run out, and settles every reveal that is overdue
+    pub fn tick(&mut self, now: Instant) {
+        for room in self.rooms.values_mut() {
+            room.tick(now, &mut self.outbox);
+        }
+    }
+
+    // When the next countdown or reveal runs out, if one is running
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.rooms.values().filter_map(Room::deadline).min()
+    }
+
+    // Messages to send since the last drain
+    pub fn drain(&mut self) -> Vec<(ClientId, ServerMessage)> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    pub fn player(&self, client: ClientId) -> Option<Player> {
+        self.clients
+            .get(&client)
+            .map(|(_, seat)| seat_player(*seat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Element;
+
+    const RED: ClientId = 1;
+    const BLUE: ClientId = 2;
+
+    fn started(rules: MatchRules) -> (Relay, Instant) {
+        let mut relay = Relay::default();
+        let now = Instant::now();
+        for client in [RED, BLUE] {
+            let join = ClientMessage::Join {
+                room: "test".to_string(),
+                settings: GameSettings::default(),
+                rules,
+            };
+            relay.receive(client, join, now);
+        }
+        relay.drain();
+        (relay, now)
+    }
+
+    fn ready(relay: &mut Relay, phase: Phase, now: Instant) {
+        for client in [RED, BLUE] {
+            let state = MatchState::default();
+            relay.receive(client, ClientMessage::Ready { phase, state }, now);
+        }
+    }
+
+    fn went(messages: &[(ClientId, ServerMessage)]) -> bool {
+        messages
+            .iter()
+            .any(|(_, message)| matches!(message, ServerMessage::Go { .. }))
+    }
+
+    #[test]
+    fn join_clamps_the_hosts_rules() {
+        let mut relay = Relay::default();
+        let rules = MatchRules {
+            countdown_time: f32::NAN,
+            ..MatchRules::standard()
+        };
+        for client in [RED, BLUE] {
+            let join = ClientMessage::Join {
+                room: "test".to_string(),
+                settings: GameSettings::default(),
+                rules,
+            };
+            relay.receive(client, join, Instant::now());
+        }
+        let start = relay
+            .drain()
+            .into_iter()
+            .find_map(|(_, message)| match message {
+                ServerMessage::Start { rules, .. } => Some(rules),
+                _ => None,
+            });
+        assert_eq!(start, Some(rules.clamped()));
+
+        // The countdown can start without panicking
+        ready(&mut relay, 1, Instant::now());
+        assert!(went(&relay.drain()));
+    }
+
+    #[test]
+    fn ready_only_opens_the_next_phase() {
+        let (mut relay, now) = started(MatchRules::standard());
+        ready(&mut relay, 2, now);
+        assert!(!went(&relay.drain()));
+        ready(&mut relay, 1, now);
+        assert!(went(&relay.drain()));
+    }
+
+    const WATER: Choice = Choice::Element(Element::Water);
+    const FIRE: Choice = Choice::Element(Element::Fire);
+
+    fn player(client: ClientId) -> Player {
+        if client == RED {
+            Player::One
+        } else {
+            Player::Two
+        }
+    }
+
+    fn commit_pick(relay: &mut Relay, client: ClientId, choice: Choice, nonce: u64, now: Instant) {
+        let commitment = commit(1, player(client), choice, nonce);
+        relay.receive(
+            client,
+            ClientMessage::Commit {
+                phase: 1,
+                commitment,
+            },
+            now,
+        );
+    }
+
+    fn reveal(relay: &mut Relay, client: ClientId, choice: Choice, nonce: u64, now: Instant) {
+        let message = ClientMessage::Reveal {
+            phase: 1,
+            choice,
+            nonce,
+        };
+        relay.receive(client, message, now);
+    }
+
+    fn lock_both(relay: &mut Relay, now: Instant) {
+        for client in [RED, BLUE] {
+            relay.receive(client, ClientMessage::Lock { phase: 1 }, now);
+        }
+    }
+
+    // The final picks Red was sent, in order
+    fn finals(messages: &[(ClientId, ServerMessage)]) -> Vec<(Player, Choice, bool)> {
+        messages
+            .iter()
+            .filter_map(|(client, message)| match message {
+                ServerMessage::Final {
+                    player,
+                    choice,
+                    forfeit,
+                    ..
+                } if *client == RED => Some((*player, *choice, *forfeit)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Both players committed and locked in, so the countdown has closed
+    fn closed() -> (Relay, Instant) {
+        let (mut relay, now) = started(MatchRules::standard());
+        ready(&mut relay, 1, now);
+        commit_pick(&mut relay, RED, WATER, 7, now);
+        commit_pick(&mut relay, BLUE, FIRE, 9, now);
+        lock_both(&mut relay, now);
+        relay.drain();
+        (relay, now)
+    }
+
+    #[test]
+    fn matching_reveals_are_passed_on() {
+        let (mut relay, now) = closed();
+        reveal(&mut relay, RED, WATER, 7, now);
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        assert_eq!(
+            finals(&relay.drain()),
+            vec![(Player::One, WATER, false), (Player::Two, FIRE, false)]
+        );
+    }
+
+    #[test]
+    fn finals_wait_for_both_reveals() {
+        let (mut relay, now) = closed();
+        reveal(&mut relay, RED, WATER, 7, now);
+        assert!(finals(&relay.drain()).is_empty());
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        assert_eq!(finals(&relay.drain()).len(), 2);
+    }
+
+    #[test]
+    fn mismatched_reveal_forfeits() {
+        let (mut relay, now) = closed();
+        reveal(&mut relay, RED, FIRE, 7, now);
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        assert_eq!(
+            finals(&relay.drain()),
+            vec![
+                (Player::One, Choice::None, true),
+                (Player::Two, FIRE, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn no_commitment_times_out() {
+        let (mut relay, now) = started(MatchRules::standard());
+        ready(&mut relay, 1, now);
+        commit_pick(&mut relay, BLUE, FIRE, 9, now);
+        lock_both(&mut relay, now);
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        assert_eq!(
+            finals(&relay.drain()),
+            vec![
+                (Player::One, Choice::None, false),
+                (Player::Two, FIRE, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn commit_after_close_is_ignored() {
+        let (mut relay, now) = closed();
+        commit_pick(&mut relay, RED, FIRE, 8, now);
+        reveal(&mut relay, RED, FIRE, 8, now);
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        assert_eq!(finals(&relay.drain())[0], (Player::One, Choice::None, true));
+    }
+
+    #[test]
+    fn withheld_reveal_forfeits_at_the_deadline() {
+        let (mut relay, now) = closed();
+        reveal(&mut relay, BLUE, FIRE, 9, now);
+        relay.tick(now + REVEAL_TIMEOUT / 2);
+        assert!(finals(&relay.drain()).is_empty());
+        assert_eq!(relay.next_deadline(), Some(now + REVEAL_TIMEOUT));
+
+        relay.tick(now + REVEAL_TIMEOUT);
+        assert_eq!(
+            finals(&relay.drain()),
+            vec![
+                (Player::One, Choice::None, true),
+                (Player::Two, FIRE, false)
+            ]
+        );
+        assert_eq!(relay.next_deadline(), None);
+    }
+
+    #[test]
+    fn leaving_instead_of_revealing_forfeits() {
+        let (mut relay, now) = closed();
+        reveal(&mut relay, RED, WATER, 7, now);
+        relay.leave(BLUE, now);
+        let messages = relay.drain();
+        assert_eq!(
+            finals(&messages),
+            vec![
+                (Player::One, WATER, false),
+                (Player::Two, Choice::None, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn go_carries_the_hosts_state() {
+        let (mut relay, now) = started(MatchRules::standard());
+        let mut host_state = MatchState::default();
+        host_state.player_two.health -= 1;
+        let state = host_state.clone();
+        relay.receive(RED, ClientMessage::Ready { phase: 1, state }, now);
+        let state = MatchState::default();
+        relay.receive(BLUE, ClientMessage::Ready { phase: 1, state }, now);
+        let sent = relay
+            .drain()
+            .into_iter()
+            .find_map(|(client, message)| match message {
+                ServerMessage::Go { host_state, .. } if client == BLUE => host_state,
+                _ => None,
+            });
+        assert_eq!(sent, Some(host_state));
+    }
+}
@@ -0,0 +1,146 @@
+// Serves the relay over WebSockets, for swirly-relay and for games hosting on the LAN
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::log::{info, warn};
+use tungstenite::{Error, Message};
+
+use super::protocol::{decode, encode, ClientMessage, ServerMessage};
+use super::relay::{ClientId, Relay};
+
+// How long a connection waits for a message before sending what is queued for it
+const POLL: Duration = Duration::from_millis(10);
+// How long the hub and the listener sleep while nothing is happening
+const IDLE: Duration = Duration::from_millis(100);
+
+enum Event {
+    Connected(ClientId, Sender<ServerMessage>),
+    Received(ClientId, ClientMessage),
+    Closed(ClientId),
+}
+
+// Runs the relay on the listener until `stop` is set
+pub fn serve(listener: TcpListener, stop: Arc<AtomicBool>) -> io::Result<()> {
+    listener.set_nonblocking(true)?;
+    let (events, hub) = mpsc::channel();
+    let accept_stop = stop.clone();
+    thread::spawn(move || accept(listener, events, accept_stop));
+    run_hub(hub, stop);
+    Ok(())
+}
+
+fn accept(listener: TcpListener, events: Sender<Event>, stop: Arc<AtomicBool>) {
+    let mut ids = 1..;
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let id = ids.next().unwrap_or_default();
+                let events = events.clone();
+                thread::spawn(move || connect(id, stream, events));
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(IDLE),
+            Err(e) => warn!("Failed to accept a connection: {}", e),
+        }
+    }
+}
+
+// Owns the rooms, waking for every message and whenever a countdown runs out
+fn run_hub(events: Receiver<Event>, stop: Arc<AtomicBool>) {
+    let mut relay = Relay::default();
+    let mut clients = HashMap::new();
+    while !stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        let timeout = relay.next_deadline().map_or(IDLE, |deadline| {
+            deadline.saturating_duration_since(now).min(IDLE)
+        });
+        match events.recv_timeout(timeout) {
+            Ok(Event::Connected(id, sender)) => {
+                clients.insert(id, sender);
+            }
+            Ok(Event::Received(id, message)) => relay.receive(id, message, Instant::now()),
+            Ok(Event::Closed(id)) => {
+                if let Some(player) = relay.player(id) {
+                    info!("Client {} ({:?}) left", id, player);
+                }
+                relay.leave(id, Instant::now());
+                clients.remove(&id);
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        relay.tick(Instant::now());
+        for (id, message) in relay.drain() {
+            if let ServerMessage::Welcome { player } = &message {
+                info!("Client {} joined as {:?}", id, player);
+            }
+            if let Some(sender) = clients.get(&id) {
+                let _ = sender.send(message);
+            }
+        }
+    }
+}
+
+// Shuttles one client's frames to and from the hub until either side hangs up
+fn connect(id: ClientId, stream: TcpStream, events: Sender<Event>) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_nodelay(true);
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("Handshake with client {} failed: {}", id, e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL)) {
+        warn!("Client {}: {}", id, e);
+        return;
+    }
+    let (sender, outgoing) = mpsc::channel();
+    if events.send(Event::Connected(id, sender)).is_err() {
+        return;
+    }
+
+    'connection: loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match decode(&text) {
+                Some(message) => {
+                    if events.send(Event::Received(id, message)).is_err() {
+                        break;
+                    }
+                }
+                None => warn!("Client {} sent {:?}", id, text),
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => (),
+            Err(Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => break,
+        }
+        loop {
+            match outgoing.try_recv() {
+                Ok(message) => {
+                    let Some(text) = encode(&message) else {
+                        continue;
+                    };
+                    if socket.send(Message::text(text)).is_err() {
+                        break 'connection;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                // The hub has shut down
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return;
+                }
+            }
+        }
+    }
+    let _ = events.send(Event::Closed(id));
+}
@@ -0,0 +1,81 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+
+use crate::{personality::Personality, schedule::GameSet};
+
+// Every named opponent, in the order they appear on the menu
+const OPPONENTS: [&str; 3] = [
+    "bots/stubborn_stan.bot.ron",
+    "bots/chaos_carl.bot.ron",
+    "bots/fire_fanatic.bot.ron",
+];
+
+#[derive(Asset, TypePath, Debug)]
+pub struct PersonalityAsset(pub Personality);
+
+#[derive(Default)]
+struct PersonalityLoader;
+
+impl AssetLoader for PersonalityLoader {
+    type Asset = PersonalityAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let personality: Personality = ron::de::from_bytes(&bytes)?;
+        Ok(PersonalityAsset(personality.validated()?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bot.ron"]
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct OpponentHandles(pub Vec<Handle<PersonalityAsset>>);
+
+impl OpponentHandles {
+    // Opponents that have finished loading
+    pub fn loaded<'a>(
+        &'a self,
+        assets: &'a Assets<PersonalityAsset>,
+    ) -> impl Iterator<Item = &'a Personality> {
+        self.0
+            .iter()
+            .filter_map(|handle| assets.get(handle))
+            .map(|asset| &asset.0)
+    }
+
+    pub fn find(&self, name: &str, assets: &Assets<PersonalityAsset>) -> Option<Personality> {
+        self.loaded(assets)
+            .find(|personality| personality.name == name)
+            .cloned()
+    }
+}
+
+pub struct OpponentsPlugin;
+
+impl Plugin for OpponentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<PersonalityAsset>();
+        app.init_asset_loader::<PersonalityLoader>();
+        app.init_resource::<OpponentHandles>();
+        app.add_systems(PreStartup, setup.in_set(GameSet::Flow));
+    }
+}
+
+fn setup(asset_server: Res<AssetServer>, mut handles: ResMut<OpponentHandles>) {
+    handles.0 = OPPONENTS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+}
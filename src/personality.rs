@@ -0,0 +1,215 @@
+// Named CPU opponents described by data rather than code. Each personality is a
+// handful of leanings layered over random picks.
+use std::cmp::Ordering;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::{BotStrategy, MatchView};
+use crate::types::{Action, Choice, Element, Outcome, Player};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Personality {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // Relative weight of each element, anything not listed weighs 1
+    #[serde(default)]
+    pub element_bias: Vec<(Element, f32)>,
+    // Relative weight of each action, anything not listed weighs 1
+    #[serde(default)]
+    pub action_bias: Vec<(Action, f32)>,
+    // Chance of repeating an action that just won an exchange
+    #[serde(default)]
+    pub stickiness: f64,
+    // Chance of playing its doubled action while holding the advantage
+    #[serde(default)]
+    pub aggression: f64,
+    // Chance of countering the opponent's doubled action while they hold the advantage
+    #[serde(default)]
+    pub caution: f64,
+    // Seconds before the first pick
+    pub reaction_time: (f32, f32),
+    // Chance of each possible feint before the final pick
+    #[serde(default)]
+    pub feint_chance: f64,
+    // Chance that, when feinting, the first pick is the real one
+    #[serde(default)]
+    pub tell_chance: f64,
+}
+
+impl Personality {
+    // Data files are hand-written, so reject numbers that can't be used and
+    // pull everything else into range
+    pub fn validated(mut self) -> Result<Self, String> {
+        let weights = self.element_bias.iter().map(|(_, weight)| *weight);
+        let weights = weights.chain(self.action_bias.iter().map(|(_, weight)| *weight));
+        if !weights
+            .chain([self.reaction_time.0, self.reaction_time.1])
+            .all(f32::is_finite)
+        {
+            return Err(format!(
+                "{} has a weight or time that isn't a number",
+                self.name
+            ));
+        }
+        for chance in [
+            &mut self.stickiness,
+            &mut self.aggression,
+            &mut self.caution,
+            &mut self.feint_chance,
+            &mut self.tell_chance,
+        ] {
+            if !chance.is_finite() {
+                return Err(format!("{} has a chance that isn't a number", self.name));
+            }
+            *chance = chance.clamp(0.0, 1.0);
+        }
+        for (_, weight) in &mut self.element_bias {
+            *weight = weight.max(0.0);
+        }
+        for (_, weight) in &mut self.action_bias {
+            *weight = weight.max(0.0);
+        }
+        // The range may be listed either way round
+        let (a, b) = self.reaction_time;
+        self.reaction_time = (a.min(b).max(0.0), a.max(b).max(0.0));
+        Ok(self)
+    }
+}
+
+fn bias<T: PartialEq>(biases: &[(T, f32)], pick: &T) -> f32 {
+    biases
+        .iter()
+        .find(|(other, _)| other == pick)
+        .map_or(1.0, |(_, weight)| weight.max(0.0))
+}
+
+pub struct PersonalityBot {
+    personality: Personality,
+    last_action: Option<Action>,
+    // The action that won the last exchange, if this bot won it
+    last_winner: Option<Action>,
+}
+
+impl PersonalityBot {
+    pub fn new(personality: Personality) -> Self {
+        Self {
+            personality,
+            last_action: None,
+            last_winner: None,
+        }
+    }
+
+    fn leaning(&self, view: &MatchView, rng: &mut dyn RngCore) -> Option<Action> {
+        let personality = &self.personality;
+        let ruleset = view.ruleset();
+        if let Some(action) = self.last_winner {
+            if rng.gen_bool(personality.stickiness.clamp(0.0, 1.0)) {
+                return Some(action);
+            }
+        }
+        match view.attacker() {
+            Some(attacker) if attacker == view.player => {
+                if !rng.gen_bool(personality.aggression.clamp(0.0, 1.0)) {
+                    return None;
+                }
+                match ruleset.get_complement(view.own.element) {
+                    Choice::Action(action) => Some(action),
+                    _ => None,
+                }
+            }
+            Some(_) => {
+                if !rng.gen_bool(personality.caution.clamp(0.0, 1.0)) {
+                    return None;
+                }
+                let threat = ruleset.get_complement(view.opponent_element);
+                ruleset.actions().into_iter().find(|action| {
+                    threat != Choice::None
+                        && ruleset.compare(Choice::Action(*action), threat) == Ordering::Greater
+                })
+            }
+            None => None,
+        }
+    }
+}
+
+impl BotStrategy for PersonalityBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        let elements = view.ruleset().elements();
+        elements
+            .choose_weighted(&mut rng, |element| {
+                bias(&self.personality.element_bias, element)
+            })
+            .copied()
+            .unwrap_or_else(|_| view.ruleset().random_element(&mut rng))
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        let action = self.leaning(view, rng).unwrap_or_else(|| {
+            let actions = view.ruleset().actions();
+            actions
+                .choose_weighted(&mut rng, |action| {
+                    bias(&self.personality.action_bias, action)
+                })
+                .copied()
+                .unwrap_or_else(|_| view.ruleset().random_action(&mut rng))
+        });
+        self.last_action = Some(action);
+        action
+    }
+
+    fn observe_exchange(&mut self, view: &MatchView, _action: Action, outcome: Outcome) {
+        let won = matches!(
+            (outcome, view.player),
+            (Outcome::PlayerOne, Player::One) | (Outcome::PlayerTwo, Player::Two)
+        );
+        self.last_winner = if won { self.last_action } else { None };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn personality() -> Personality {
+        Personality {
+            name: "Test".to_string(),
+            description: String::new(),
+            element_bias: vec![(Element::Fire, -2.0)],
+            action_bias: vec![],
+            stickiness: 1.5,
+            aggression: -0.5,
+            caution: 0.5,
+            reaction_time: (0.8, -0.2),
+            feint_chance: 0.0,
+            tell_chance: 0.0,
+        }
+    }
+
+    #[test]
+    fn validated_pulls_values_into_range() {
+        let personality = personality().validated().unwrap();
+        assert_eq!(personality.stickiness, 1.0);
+        assert_eq!(personality.aggression, 0.0);
+        assert_eq!(personality.caution, 0.5);
+        assert_eq!(personality.element_bias, vec![(Element::Fire, 0.0)]);
+        assert_eq!(personality.reaction_time, (0.0, 0.8));
+    }
+
+    #[test]
+    fn validated_rejects_nan() {
+        let mut nan_chance = personality();
+        nan_chance.tell_chance = f64::NAN;
+        assert!(nan_chance.validated().is_err());
+
+        let mut nan_weight = personality();
+        nan_weight.action_bias = vec![(Action::Hand, f32::NAN)];
+        assert!(nan_weight.validated().is_err());
+
+        let mut infinite_time = personality();
+        infinite_time.reaction_time.1 = f32::INFINITY;
+        assert!(infinite_time.validated().is_err());
+    }
+}
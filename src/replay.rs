@@ -0,0 +1,442 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    combo::GameData,
+    config::START_STATE,
+    events::{ExchangeEvent, SelectActionEvent, SelectElementEvent},
+    rng::GameRng,
+    rules::MatchRules,
+    schedule::GameSet,
+    settings::{GameMode, GameSettings},
+    state::{GameState, UiState},
+    types::{Choice, Outcome, Player},
+};
+
+// Bump whenever the layout of MatchRecord changes
+pub const REPLAY_VERSION: u32 = 4;
+#[cfg(not(target_arch = "wasm32"))]
+const REPLAY_DIR: &str = "replays";
+#[cfg(not(target_arch = "wasm32"))]
+const REPLAY_FILE: &str = "replays/last_match.ron";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRecord {
+    pub elements: (Choice, Choice),
+    pub actions: (Choice, Choice),
+    pub outcome: Outcome,
+    // Health of both players after the exchange was processed
+    pub health: (i32, i32),
+    pub advantage: Player,
+    #[serde(default)]
+    pub element_timeouts: (bool, bool),
+    #[serde(default)]
+    pub action_timeouts: (bool, bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub version: u32,
+    pub seed: u64,
+    pub settings: GameSettings,
+    pub rules: MatchRules,
+    pub exchanges: Vec<ExchangeRecord>,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MatchRecorder {
+    exchanges: Vec<ExchangeRecord>,
+    last_match: Option<MatchRecord>,
+}
+
+impl MatchRecorder {
+    pub fn has_replay(&self) -> bool {
+        if self.last_match.is_some() {
+            return true;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::path::Path::new(REPLAY_FILE).exists()
+        }
+        #[cfg(target_arch = "wasm32")]
+        false
+    }
+
+    // Load the last recorded match, preferring the file on disk
+    pub fn load_replay(&self) -> Option<MatchRecord> {
+        #[cfg(not(target_arch = "wasm32"))]
+        match std::fs::read_to_string(REPLAY_FILE)
+            .map_err(|e| e.to_string())
+            .and_then(|file| parse_replay(&file))
+        {
+            Ok(record) => return Some(record),
+            Err(e) => warn!("Could not load {}: {}", REPLAY_FILE, e),
+        }
+        self.last_match.clone()
+    }
+}
+
+// Read a saved match, refusing one recorded in another layout
+#[cfg(any(test, not(target_arch = "wasm32")))]
+fn parse_replay(contents: &str) -> Result<MatchRecord, String> {
+    let record = ron::from_str::<MatchRecord>(contents).map_err(|e| e.to_string())?;
+    if record.version != REPLAY_VERSION {
+        return Err(format!(
+            "replay version {} is not supported (expected {})",
+            record.version, REPLAY_VERSION
+        ));
+    }
+    Ok(record)
+}
+
+// Present while a recorded match is being played back
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    record: MatchRecord,
+    exchange: usize,
+    previous_settings: GameSettings,
+    previous_rules: MatchRules,
+}
+
+impl ReplayPlayback {
+    fn current(&self) -> Option<&ExchangeRecord> {
+        self.record.exchanges.get(self.exchange)
+    }
+
+    // Timed out picks were filled in when recorded, so the record says who timed out
+    pub fn timed_out(&self, player: Player, state: GameState) -> bool {
+        let Some(exchange) = self.current() else {
+            return false;
+        };
+        let timeouts = match state {
+            GameState::SelectElement => exchange.element_timeouts,
+            GameState::SelectAction => exchange.action_timeouts,
+            _ => return false,
+        };
+        match player {
+            Player::One => timeouts.0,
+            Player::Two => timeouts.1,
+        }
+    }
+}
+
+// Swap the settings over to the recording, restoring them once the replay ends
+pub fn start_replay(
+    commands: &mut Commands,
+    settings: &mut GameSettings,
+    rules: &mut MatchRules,
+    record: MatchRecord,
+) {
+    info!(
+        "Watching replay with seed {} ({} exchanges)",
+        record.seed,
+        record.exchanges.len()
+    );
+    let previous_settings = std::mem::replace(
+        settings,
+        GameSettings {
+            game_mode: GameMode::Replay,
+            seed: Some(record.seed),
+            ..record.settings.clone()
+        },
+    );
+    let previous_rules = std::mem::replace(rules, record.rules);
+    commands.insert_resource(ReplayPlayback {
+        previous_settings,
+        previous_rules,
+        exchange: 0,
+        record,
+    });
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchRecorder>();
+        app.add_systems(OnEnter(START_STATE), reset_recorder.in_set(GameSet::Flow));
+        app.add_systems(Update, record_exchanges.in_set(GameSet::Flow));
+        app.add_systems(OnEnter(GameState::GameOver), save_record);
+        app.add_systems(
+            Update,
+            (
+                feed_elements.run_if(in_state(GameState::SelectElement)),
+                feed_actions.run_if(in_state(GameState::SelectAction)),
+            )
+                .run_if(in_state(UiState::Countdown))
+                .run_if(resource_exists::<ReplayPlayback>)
+                .in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            Update,
+            advance_playback
+                .run_if(resource_exists::<ReplayPlayback>)
+                .in_set(GameSet::Flow),
+        );
+        app.add_systems(
+            OnExit(GameState::GameOver),
+            end_replay.run_if(resource_exists::<ReplayPlayback>),
+        );
+    }
+}
+
+fn reset_recorder(mut recorder: ResMut<MatchRecorder>) {
+    recorder.exchanges.clear();
+}
+
+fn record_exchanges(mut reader: EventReader<ExchangeEvent>, mut recorder: ResMut<MatchRecorder>) {
+    for event in reader.read() {
+        recorder.exchanges.push(ExchangeRecord {
+            elements: (event.player_one.element, event.player_two.element),
+            actions: (event.player_one.action, event.player_two.action),
+            outcome: event.outcome,
+            health: event.health,
+            advantage: event.advantage,
+            element_timeouts: event.element_timeouts,
+            action_timeouts: event.action_timeouts,
+        });
+    }
+}
+
+fn save_record(
+    mut recorder: ResMut<MatchRecorder>,
+    settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+    rng: Res<GameRng>,
+) {
+    // Don't record over the match that is being watched
+    if settings.game_mode == GameMode::Replay {
+        return;
+    }
+    let record = MatchRecord {
+        version: REPLAY_VERSION,
+        seed: rng.seed(),
+        settings: settings.clone(),
+        rules: *rules,
+        exchanges: std::mem::take(&mut recorder.exchanges),
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    match ron::ser::to_string_pretty(&record, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => {
+            if let Err(e) = std::fs::create_dir_all(REPLAY_DIR)
+                .and_then(|_| std::fs::write(REPLAY_FILE, contents))
+            {
+                warn!("Could not write {}: {}", REPLAY_FILE, e);
+            }
+        }
+        Err(e) => warn!("Could not serialise replay: {}", e),
+    }
+
+    recorder.last_match = Some(record);
+}
+
+fn feed_elements(
+    playback: Res<ReplayPlayback>,
+    mut game_data: ResMut<GameData>,
+    mut writer: EventWriter<SelectElementEvent>,
+) {
+    let Some(exchange) = playback.current() else {
+        return;
+    };
+    game_data
+        .player_one
+        .select_element(Player::One, exchange.elements.0, &mut writer);
+    game_data
+        .player_two
+        .select_element(Player::Two, exchange.elements.1, &mut writer);
+}
+
+fn feed_actions(
+    playback: Res<ReplayPlayback>,
+    mut game_data: ResMut<GameData>,
+    mut writer: EventWriter<SelectActionEvent>,
+) {
+    let Some(exchange) = playback.current() else {
+        return;
+    };
+    game_data
+        .player_one
+        .select_action(Player::One, exchange.actions.0, &mut writer);
+    game_data
+        .player_two
+        .select_action(Player::Two, exchange.actions.1, &mut writer);
+}
+
+fn advance_playback(mut reader: EventReader<ExchangeEvent>, mut playback: ResMut<ReplayPlayback>) {
+    for event in reader.read() {
+        if let Some(exchange) = playback.current() {
+            if exchange.health != event.health || exchange.outcome != event.outcome {
+                warn!(
+                    "Replay diverged at exchange {}: recorded {:?} {:?}, got {:?} {:?}",
+                    playback.exchange,
+                    exchange.outcome,
+                    exchange.health,
+                    event.outcome,
+                    event.health
+                );
+            }
+        }
+        playback.exchange += 1;
+    }
+}
+
+fn end_replay(
+    mut commands: Commands,
+    playback: Res<ReplayPlayback>,
+    mut settings: ResMut<GameSettings>,
+    mut rules: ResMut<MatchRules>,
+) {
+    *settings = playback.previous_settings.clone();
+    *rules = playback.previous_rules;
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{ChoiceSelection, MatchState, Ruleset, Transition};
+    use crate::settings::{GameVariant, SetLength};
+    use crate::types::{Action, Element};
+
+    fn pick(element: Element, action: Action) -> ChoiceSelection {
+        ChoiceSelection {
+            element: Choice::Element(element),
+            action: Choice::Action(action),
+        }
+    }
+
+    // A best of three that Red takes two rounds to nil, with a combo breaker and a
+    // drawn opening along the way
+    fn picks() -> Vec<(ChoiceSelection, ChoiceSelection)> {
+        use Action::*;
+        use Element::*;
+        let red = pick(Water, Toilet);
+        let blue = pick(Fire, Hand);
+        vec![
+            (pick(Fire, Hand), pick(Grass, Underwear)),
+            (pick(Fire, Hand), pick(Grass, Toilet)),
+            (red, red),
+            (red, blue),
+            (red, blue),
+            (red, blue),
+            (red, blue),
+            (red, blue),
+        ]
+    }
+
+    fn new_state(record: &MatchRecord) -> MatchState {
+        let ruleset = match record.settings.variant {
+            GameVariant::Classic => Ruleset::standard(),
+            GameVariant::Extended => Ruleset::extended(),
+        };
+        MatchState::new(
+            ruleset,
+            record.rules,
+            record.settings.set_length.rounds_to_win(),
+        )
+    }
+
+    // Play one exchange the way the game does, returning what it records
+    fn exchange(
+        state: &mut MatchState,
+        one: ChoiceSelection,
+        two: ChoiceSelection,
+    ) -> (Outcome, (i32, i32), Player) {
+        let report = state.step(one, two);
+        let recorded = (
+            report.result.outcome,
+            (state.player_one.health, state.player_two.health),
+            state.advantage,
+        );
+        if let Transition::RoundOver(_) = report.transition {
+            state.next_round();
+        }
+        recorded
+    }
+
+    fn recorded_match() -> MatchRecord {
+        let mut record = MatchRecord {
+            version: REPLAY_VERSION,
+            seed: 7,
+            settings: GameSettings {
+                set_length: SetLength::BestOfThree,
+                seed: Some(7),
+                ..default()
+            },
+            rules: MatchRules::standard(),
+            exchanges: Vec::new(),
+        };
+        let mut state = new_state(&record);
+        for (one, two) in picks() {
+            let (outcome, health, advantage) = exchange(&mut state, one, two);
+            record.exchanges.push(ExchangeRecord {
+                elements: (one.element, two.element),
+                actions: (one.action, two.action),
+                outcome,
+                health,
+                advantage,
+                element_timeouts: (false, false),
+                action_timeouts: (false, false),
+            });
+        }
+        record
+    }
+
+    fn saved(record: &MatchRecord) -> String {
+        ron::ser::to_string_pretty(record, ron::ser::PrettyConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn saved_replay_loads_unchanged() {
+        let record = recorded_match();
+        assert_eq!(parse_replay(&saved(&record)), Ok(record));
+    }
+
+    #[test]
+    fn other_replay_versions_are_refused() {
+        let record = MatchRecord {
+            version: REPLAY_VERSION - 1,
+            ..recorded_match()
+        };
+        assert!(parse_replay(&saved(&record)).is_err());
+    }
+
+    #[test]
+    fn replayed_picks_reproduce_the_match() {
+        let record = parse_replay(&saved(&recorded_match())).unwrap();
+        let health: Vec<_> = record.exchanges.iter().map(|e| e.health).collect();
+        assert_eq!(
+            health,
+            [
+                (5, 3),
+                (4, 3),
+                (4, 3),
+                (4, 1),
+                (4, -1),
+                (5, 3),
+                (5, 1),
+                (5, -1)
+            ]
+        );
+
+        let mut state = new_state(&record);
+        for (index, recorded) in record.exchanges.iter().enumerate() {
+            let one = ChoiceSelection {
+                element: recorded.elements.0,
+                action: recorded.actions.0,
+            };
+            let two = ChoiceSelection {
+                element: recorded.elements.1,
+                action: recorded.actions.1,
+            };
+            assert_eq!(
+                exchange(&mut state, one, two),
+                (recorded.outcome, recorded.health, recorded.advantage),
+                "exchange {}",
+                index
+            );
+        }
+        assert_eq!(state.player_one.round_wins, 2);
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::{config::START_STATE, schedule::GameSet, settings::GameSettings};
+
+// Keeps the cosmetic stream apart from GameRng when both come from the same seed
+const COSMETIC_SALT: u64 = 0x5eed_c0de_a0d1_0000;
+
+// Every random decision in a match goes through this, so a match can be
+// reproduced from its seed
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+// Sounds and other flourishes draw from their own stream, so how often they play
+// can't shift the picks drawn from GameRng
+#[derive(Resource, Debug)]
+pub struct CosmeticRng(StdRng);
+
+impl CosmeticRng {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed ^ COSMETIC_SALT))
+    }
+}
+
+impl Default for CosmeticRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl RngCore for CosmeticRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>();
+        app.init_resource::<CosmeticRng>();
+        app.add_systems(OnEnter(START_STATE), seed_match.in_set(GameSet::Flow));
+    }
+}
+
+// Reseed at the start of every match, from the settings if a seed was given
+fn seed_match(
+    mut rng: ResMut<GameRng>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    settings: Res<GameSettings>,
+) {
+    *rng = match settings.seed {
+        Some(seed) => GameRng::new(seed),
+        None => GameRng::default(),
+    };
+    // A replay sounds the same as the match it recorded
+    *cosmetic_rng = CosmeticRng::new(rng.seed());
+    info!("Match seed: {}", rng.seed());
+}
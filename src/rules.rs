@@ -0,0 +1,906 @@
+// Pure match rules. Nothing in here touches Bevy, so a match can be stepped
+// through (and tested or simulated) without an App, assets or any UI.
+use std::cmp::Ordering;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{COUNTDOWN_TIME, MAX_HEALTH, REVEAL_TIME};
+use crate::types::{Action, Choice, Element, Outcome, Player};
+
+// Which choices beat which, and which element doubles the damage of which action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ruleset {
+    // Each pair is (winner, loser)
+    pub elements: Vec<(Element, Element)>,
+    pub actions: Vec<(Action, Action)>,
+    pub complements: Vec<(Element, Action)>,
+}
+
+impl Ruleset {
+    pub fn compare(&self, first: Choice, second: Choice) -> Ordering {
+        match (first, second) {
+            (Choice::None, Choice::None) => Ordering::Equal,
+            (Choice::None, _) => Ordering::Less,
+            (_, Choice::None) => Ordering::Greater,
+            (Choice::Element(e1), Choice::Element(e2)) => beats(&self.elements, e1, e2),
+            (Choice::Action(a1), Choice::Action(a2)) => beats(&self.actions, a1, a2),
+            _ => Ordering::Equal,
+        }
+    }
+
+    // The element that doubles an action, or the action doubled by an element
+    pub fn get_complement(&self, choice: Choice) -> Choice {
+        match choice {
+            Choice::Element(element) => self
+                .complements
+                .iter()
+                .find(|(e, _)| *e == element)
+                .map_or(Choice::None, |(_, a)| Choice::Action(*a)),
+            Choice::Action(action) => self
+                .complements
+                .iter()
+                .find(|(_, a)| *a == action)
+                .map_or(Choice::None, |(e, _)| Choice::Element(*e)),
+            Choice::None => Choice::None,
+        }
+    }
+
+    // Every element in the beats-graph, in the order they first appear
+    pub fn elements(&self) -> Vec<Element> {
+        nodes(&self.elements)
+    }
+
+    pub fn actions(&self) -> Vec<Action> {
+        nodes(&self.actions)
+    }
+
+    pub fn random_element(&self, rng: &mut impl Rng) -> Element {
+        *self.elements().choose(rng).unwrap_or(&Element::Fire)
+    }
+
+    pub fn random_action(&self, rng: &mut impl Rng) -> Action {
+        *self.actions().choose(rng).unwrap_or(&Action::Hand)
+    }
+
+    // A random action, twice as likely to be the one doubled by the element
+    pub fn weighted_action(&self, element: Choice, rng: &mut impl Rng) -> Action {
+        let complement = self.get_complement(element);
+        *self
+            .actions()
+            .choose_weighted(rng, |action| {
+                if Choice::Action(*action) == complement {
+                    2
+                } else {
+                    1
+                }
+            })
+            .unwrap_or(&Action::Hand)
+    }
+
+    // Compare two choices, returning who won and with what
+    pub fn resolve(&self, choice_one: Choice, choice_two: Choice) -> ResolveResult {
+        match self.compare(choice_one, choice_two) {
+            Ordering::Greater => ResolveResult {
+                outcome: Outcome::PlayerOne,
+                choice: choice_one,
+            },
+            Ordering::Less => ResolveResult {
+                outcome: Outcome::PlayerTwo,
+                choice: choice_two,
+            },
+            Ordering::Equal => ResolveResult {
+                outcome: Outcome::Draw,
+                choice: Choice::None,
+            },
+        }
+    }
+}
+
+fn beats<T: PartialEq>(graph: &[(T, T)], first: T, second: T) -> Ordering {
+    if graph.iter().any(|(w, l)| *w == first && *l == second) {
+        Ordering::Greater
+    } else if graph.iter().any(|(w, l)| *w == second && *l == first) {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    }
+}
+
+fn nodes<T: PartialEq + Copy>(graph: &[(T, T)]) -> Vec<T> {
+    let mut nodes = Vec::new();
+    for &(winner, loser) in graph {
+        for node in [winner, loser] {
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+    }
+    nodes
+}
+
+// Built-in copies of the ruleset assets, used when they haven't loaded
+impl Ruleset {
+    pub fn standard() -> Self {
+        use Action::*;
+        use Element::*;
+        Self {
+            elements: vec![(Water, Fire), (Fire, Grass), (Grass, Water)],
+            actions: vec![(Toilet, Hand), (Hand, Underwear), (Underwear, Toilet)],
+            complements: vec![(Fire, Hand), (Water, Toilet), (Grass, Underwear)],
+        }
+    }
+
+    // Every element beats the two after it in the cycle Water, Fire, Metal, Grass, Earth
+    // (and likewise for Toilet, Hand, Finger, Underwear, Elbow)
+    pub fn extended() -> Self {
+        use Action::*;
+        use Element::*;
+        Self {
+            elements: vec![
+                (Water, Fire),
+                (Water, Metal),
+                (Fire, Metal),
+                (Fire, Grass),
+                (Metal, Grass),
+                (Metal, Earth),
+                (Grass, Earth),
+                (Grass, Water),
+                (Earth, Water),
+                (Earth, Fire),
+            ],
+            actions: vec![
+                (Toilet, Hand),
+                (Toilet, Finger),
+                (Hand, Finger),
+                (Hand, Underwear),
+                (Finger, Underwear),
+                (Finger, Elbow),
+                (Underwear, Elbow),
+                (Underwear, Toilet),
+                (Elbow, Toilet),
+                (Elbow, Hand),
+            ],
+            complements: vec![
+                (Fire, Hand),
+                (Water, Toilet),
+                (Grass, Underwear),
+                (Metal, Finger),
+                (Earth, Elbow),
+            ],
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+// Tunable numbers for a match
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchRules {
+    pub starting_health: i32,
+    // Seconds to pick an element or action
+    pub countdown_time: f32,
+    // Seconds spent revealing the picks
+    pub reveal_time: f32,
+    // Damage when the winning action is doubled by the winner's element
+    pub complement_damage: i32,
+    pub base_damage: i32,
+    // How a double KO is settled
+    #[serde(default)]
+    pub tiebreak: TiebreakRule,
+    // What happens to a player who doesn't pick in time
+    #[serde(default)]
+    pub timeout: TimeoutPolicy,
+}
+
+impl MatchRules {
+    pub fn quick() -> Self {
+        Self {
+            starting_health: 3,
+            countdown_time: 2.5,
+            reveal_time: 1.0,
+            ..Self::standard()
+        }
+    }
+
+    pub fn standard() -> Self {
+        Self {
+            starting_health: MAX_HEALTH,
+            countdown_time: COUNTDOWN_TIME,
+            reveal_time: REVEAL_TIME,
+            complement_damage: 2,
+            base_damage: 1,
+            tiebreak: TiebreakRule::SuddenDeath,
+            timeout: TimeoutPolicy::AutoLoss,
+        }
+    }
+
+    pub fn marathon() -> Self {
+        Self {
+            starting_health: 10,
+            countdown_time: 5.0,
+            ..Self::standard()
+        }
+    }
+
+    // Keep every field in the range the Custom Match screen allows, so rules read from
+    // a file or another player can't stall or crash a match
+    pub fn clamped(self) -> Self {
+        let seconds = |value: f32, min: f32, max: f32, default: f32| {
+            if value.is_finite() {
+                value.clamp(min, max)
+            } else {
+                default
+            }
+        };
+        let base_damage = self.base_damage.clamp(1, 10);
+        Self {
+            starting_health: self.starting_health.clamp(1, 20),
+            countdown_time: seconds(self.countdown_time, 1.0, 10.0, COUNTDOWN_TIME),
+            reveal_time: seconds(self.reveal_time, 0.5, 5.0, REVEAL_TIME),
+            // Doubled damage is never less than a normal hit
+            complement_damage: self.complement_damage.clamp(base_damage, 10),
+            base_damage,
+            ..self
+        }
+    }
+
+    // Name of the preset these rules match, if any
+    pub fn preset_name(&self) -> Option<&'static str> {
+        [
+            ("Quick", Self::quick()),
+            ("Standard", Self::standard()),
+            ("Marathon", Self::marathon()),
+        ]
+        .into_iter()
+        .find(|(_, preset)| preset == self)
+        .map(|(name, _)| name)
+    }
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TiebreakRule {
+    // Both players drop to one heart and keep fighting
+    #[default]
+    SuddenDeath,
+    // Whoever dealt more damage this round takes it
+    MostDamage,
+    DeclaredDraw,
+}
+
+impl TiebreakRule {
+    pub fn next(&self) -> Self {
+        match self {
+            TiebreakRule::SuddenDeath => TiebreakRule::MostDamage,
+            TiebreakRule::MostDamage => TiebreakRule::DeclaredDraw,
+            TiebreakRule::DeclaredDraw => TiebreakRule::SuddenDeath,
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        self.next().next()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TiebreakRule::SuddenDeath => "Sudden Death",
+            TiebreakRule::MostDamage => "Most Damage",
+            TiebreakRule::DeclaredDraw => "Draw",
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeoutPolicy {
+    // The empty pick loses every comparison
+    #[default]
+    AutoLoss,
+    RandomPick,
+    // Falls back to a random pick if there is nothing to repeat
+    RepeatLast,
+    // Lose a heart, then play on with a random pick
+    ForfeitHeart,
+}
+
+impl TimeoutPolicy {
+    pub fn next(&self) -> Self {
+        match self {
+            TimeoutPolicy::AutoLoss => TimeoutPolicy::RandomPick,
+            TimeoutPolicy::RandomPick => TimeoutPolicy::RepeatLast,
+            TimeoutPolicy::RepeatLast => TimeoutPolicy::ForfeitHeart,
+            TimeoutPolicy::ForfeitHeart => TimeoutPolicy::AutoLoss,
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        self.next().next().next()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeoutPolicy::AutoLoss => "Auto Loss",
+            TimeoutPolicy::RandomPick => "Random Pick",
+            TimeoutPolicy::RepeatLast => "Repeat Last",
+            TimeoutPolicy::ForfeitHeart => "Lose a Heart",
+        }
+    }
+}
+
+// Outcome of a round, or of the whole set
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchResult {
+    Winner(Player),
+    Draw,
+    // Both players were knocked out by the same exchange
+    DoubleKo,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ChoiceSelection {
+    pub element: Choice,
+    pub action: Choice,
+}
+
+impl ChoiceSelection {
+    pub fn can_double(&self, ruleset: &Ruleset) -> bool {
+        self.element != Choice::None && self.element == ruleset.get_complement(self.action)
+    }
+}
+
+#[derive(Debug)]
+pub struct ResolveResult {
+    pub outcome: Outcome,
+    pub choice: Choice,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Phase {
+    #[default]
+    SelectElement,
+    SelectAction,
+    RoundOver,
+    GameOver,
+}
+
+// What happens after an exchange has been resolved
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Transition {
+    // The opening exchange was a draw, so go back to selecting elements
+    NoAdvantage,
+    // The opening exchange was won, and the winner keeps attacking
+    Advantage(Player),
+    // Keep exchanging actions
+    Continue,
+    // The player without the advantage won, so go back to selecting elements
+    ComboBreaker,
+    // Both players were knocked out and go again on one heart each
+    SuddenDeath,
+    // The round is settled, but the set goes on
+    RoundOver(MatchResult),
+    GameOver,
+}
+
+#[derive(Debug)]
+pub struct TurnReport {
+    pub result: ResolveResult,
+    pub damage: i32,
+    pub transition: Transition,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub health: i32,
+    pub round_wins: u32,
+    // Damage dealt to the opponent this round
+    pub damage_dealt: i32,
+    // Conceded the set, which ends at the next step
+    pub forfeited: bool,
+}
+
+impl PlayerState {
+    fn new(health: i32) -> Self {
+        Self {
+            health,
+            round_wins: 0,
+            damage_dealt: 0,
+            forfeited: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchState {
+    pub player_one: PlayerState,
+    pub player_two: PlayerState,
+    // Number of actions exchanged since the last element selection
+    pub action: u32,
+    pub advantage: Player,
+    pub phase: Phase,
+    pub ruleset: Ruleset,
+    pub rules: MatchRules,
+    // Current round of the set, starting from 1
+    pub round: u32,
+    pub rounds_to_win: u32,
+    pub last_round: Option<MatchResult>,
+}
+
+impl Default for MatchState {
+    fn default() -> Self {
+        Self::new(Ruleset::default(), MatchRules::default(), 1)
+    }
+}
+
+impl MatchState {
+    pub fn new(ruleset: Ruleset, rules: MatchRules, rounds_to_win: u32) -> Self {
+        Self {
+            player_one: PlayerState::new(rules.starting_health),
+            player_two: PlayerState::new(rules.starting_health),
+            action: 0,
+            advantage: Player::default(),
+            phase: Phase::default(),
+            ruleset,
+            rules,
+            round: 1,
+            rounds_to_win,
+            last_round: None,
+        }
+    }
+
+    fn player_mut(&mut self, player: Player) -> &mut PlayerState {
+        match player {
+            Player::One => &mut self.player_one,
+            Player::Two => &mut self.player_two,
+        }
+    }
+
+    // Resolve one exchange of actions, applying damage and moving to the next phase
+    pub fn step(&mut self, p1_choice: ChoiceSelection, p2_choice: ChoiceSelection) -> TurnReport {
+        // A forfeit has already settled the round, so there is no exchange to play
+        if self.decided() {
+            let transition = self.settle();
+            self.enter(transition);
+            return TurnReport {
+                result: ResolveResult {
+                    outcome: Outcome::Draw,
+                    choice: Choice::None,
+                },
+                damage: 0,
+                transition,
+            };
+        }
+
+        // Increment Action by One
+        self.action += 1;
+        let result = self.ruleset.resolve(p1_choice.action, p2_choice.action);
+
+        // Update Healths
+        let (winner, selection) = match result.outcome {
+            Outcome::PlayerOne => (Some(Player::One), p1_choice),
+            Outcome::PlayerTwo => (Some(Player::Two), p2_choice),
+            Outcome::Draw => (None, ChoiceSelection::default()),
+        };
+        let mut damage = 0;
+        if let Some(winner) = winner {
+            damage = if selection.can_double(&self.ruleset) {
+                self.rules.complement_damage
+            } else {
+                self.rules.base_damage
+            };
+            self.player_mut(winner.opponent()).health -= damage;
+            self.player_mut(winner).damage_dealt += damage;
+        }
+
+        let transition = if self.decided() {
+            self.settle()
+        } else {
+            match (winner, self.action) {
+                (None, 1) => Transition::NoAdvantage,
+                (Some(winner), 1) => {
+                    self.advantage = winner;
+                    Transition::Advantage(winner)
+                }
+                (Some(winner), _) if winner != self.advantage => Transition::ComboBreaker,
+                _ => Transition::Continue,
+            }
+        };
+        self.enter(transition);
+
+        TurnReport {
+            result,
+            damage,
+            transition,
+        }
+    }
+
+    // Whether the round is over before the next exchange, from a knock-out or a forfeit
+    pub fn decided(&self) -> bool {
+        self.player_one.forfeited || self.player_two.forfeited || self.can_end_game()
+    }
+
+    // Ends the round, or the whole set, once it has been decided
+    fn settle(&mut self) -> Transition {
+        match (self.player_one.forfeited, self.player_two.forfeited) {
+            (true, true) => {
+                self.player_one.health = 0;
+                self.player_two.health = 0;
+                self.last_round = Some(MatchResult::DoubleKo);
+                return Transition::GameOver;
+            }
+            (true, false) | (false, true) => {
+                let loser = if self.player_one.forfeited {
+                    Player::One
+                } else {
+                    Player::Two
+                };
+                let winner = loser.opponent();
+                self.player_mut(loser).health = 0;
+                self.player_mut(winner).round_wins = self.rounds_to_win;
+                self.last_round = Some(MatchResult::Winner(winner));
+                return Transition::GameOver;
+            }
+            (false, false) => (),
+        }
+        match self.get_round_result() {
+            Some(result) => {
+                if let MatchResult::Winner(round_winner) = result {
+                    self.player_mut(round_winner).round_wins += 1;
+                }
+                self.last_round = Some(result);
+                if self.is_set_over() {
+                    Transition::GameOver
+                } else {
+                    Transition::RoundOver(result)
+                }
+            }
+            None => {
+                self.player_one.health = 1;
+                self.player_two.health = 1;
+                self.advantage = Player::default();
+                Transition::SuddenDeath
+            }
+        }
+    }
+
+    fn enter(&mut self, transition: Transition) {
+        self.phase = match transition {
+            Transition::NoAdvantage | Transition::ComboBreaker | Transition::SuddenDeath => {
+                self.action = 0;
+                Phase::SelectElement
+            }
+            Transition::Advantage(_) | Transition::Continue => Phase::SelectAction,
+            Transition::RoundOver(_) => Phase::RoundOver,
+            Transition::GameOver => Phase::GameOver,
+        };
+    }
+
+    // Heal both players for the next round of the set, keeping the round wins
+    pub fn next_round(&mut self) {
+        self.player_one.health = self.rules.starting_health;
+        self.player_two.health = self.rules.starting_health;
+        self.player_one.damage_dealt = 0;
+        self.player_two.damage_dealt = 0;
+        self.action = 0;
+        self.advantage = Player::default();
+        self.phase = Phase::SelectElement;
+        self.round += 1;
+    }
+
+    // A knock-out from a forfeited heart is settled by the next step, without an exchange
+    pub fn forfeit_heart(&mut self, player: Player) {
+        self.player_mut(player).health -= 1;
+    }
+
+    // Caught cheating, so the set goes to the opponent, or to nobody if both were
+    pub fn forfeit(&mut self, player: Player) {
+        self.player_mut(player).forfeited = true;
+    }
+
+    pub fn can_end_game(&self) -> bool {
+        self.player_one.health <= 0 || self.player_two.health <= 0
+    }
+
+    // None when the round goes to sudden death
+    fn get_round_result(&self) -> Option<MatchResult> {
+        match (self.player_one.health > 0, self.player_two.health > 0) {
+            (true, false) => return Some(MatchResult::Winner(Player::One)),
+            (false, true) => return Some(MatchResult::Winner(Player::Two)),
+            _ => (),
+        }
+        match self.rules.tiebreak {
+            TiebreakRule::SuddenDeath => None,
+            TiebreakRule::MostDamage => {
+                match self
+                    .player_one
+                    .damage_dealt
+                    .cmp(&self.player_two.damage_dealt)
+                {
+                    Ordering::Greater => Some(MatchResult::Winner(Player::One)),
+                    Ordering::Less => Some(MatchResult::Winner(Player::Two)),
+                    Ordering::Equal => Some(MatchResult::DoubleKo),
+                }
+            }
+            TiebreakRule::DeclaredDraw => Some(MatchResult::DoubleKo),
+        }
+    }
+
+    // The set ends once a player has won enough rounds, or every round has been played
+    fn is_set_over(&self) -> bool {
+        let max_rounds = self.rounds_to_win * 2 - 1;
+        self.player_one.round_wins >= self.rounds_to_win
+            || self.player_two.round_wins >= self.rounds_to_win
+            || self.round >= max_rounds
+    }
+
+    // Result of the set
+    pub fn get_result(&self) -> MatchResult {
+        if self.player_one.forfeited && self.player_two.forfeited {
+            return MatchResult::DoubleKo;
+        }
+        match self.player_one.round_wins.cmp(&self.player_two.round_wins) {
+            Ordering::Greater => MatchResult::Winner(Player::One),
+            Ordering::Less => MatchResult::Winner(Player::Two),
+            Ordering::Equal => match self.last_round {
+                Some(MatchResult::DoubleKo) => MatchResult::DoubleKo,
+                _ => MatchResult::Draw,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(element: Element, action: Action) -> ChoiceSelection {
+        ChoiceSelection {
+            element: Choice::Element(element),
+            action: Choice::Action(action),
+        }
+    }
+
+    fn standard(rounds_to_win: u32) -> MatchState {
+        MatchState::new(Ruleset::standard(), MatchRules::standard(), rounds_to_win)
+    }
+
+    #[test]
+    fn complement_doubles_damage() {
+        let mut state = standard(1);
+        let report = state.step(
+            pick(Element::Fire, Action::Hand),
+            pick(Element::Water, Action::Underwear),
+        );
+        assert_eq!(report.result.outcome, Outcome::PlayerOne);
+        assert_eq!(report.damage, 2);
+        assert_eq!(state.player_two.health, MAX_HEALTH - 2);
+        assert_eq!(state.player_one.damage_dealt, 2);
+
+        let mut state = standard(1);
+        let report = state.step(
+            pick(Element::Water, Action::Hand),
+            pick(Element::Water, Action::Underwear),
+        );
+        assert_eq!(report.damage, 1);
+        assert_eq!(state.player_two.health, MAX_HEALTH - 1);
+    }
+
+    #[test]
+    fn empty_pick_loses() {
+        let mut state = standard(1);
+        let report = state.step(
+            ChoiceSelection::default(),
+            pick(Element::Fire, Action::Underwear),
+        );
+        assert_eq!(report.result.outcome, Outcome::PlayerTwo);
+        assert_eq!(state.player_one.health, MAX_HEALTH - 1);
+    }
+
+    #[test]
+    fn opening_draw_has_no_advantage() {
+        let mut state = standard(1);
+        let report = state.step(
+            pick(Element::Fire, Action::Hand),
+            pick(Element::Water, Action::Hand),
+        );
+        assert_eq!(report.transition, Transition::NoAdvantage);
+        assert_eq!(state.phase, Phase::SelectElement);
+        assert_eq!(state.action, 0);
+    }
+
+    #[test]
+    fn winner_keeps_attacking_until_broken() {
+        let mut state = standard(1);
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Hand),
+        );
+        assert_eq!(report.transition, Transition::Advantage(Player::One));
+        assert_eq!(state.phase, Phase::SelectAction);
+
+        // A draw or another win keeps the combo going
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Toilet),
+        );
+        assert_eq!(report.transition, Transition::Continue);
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Hand),
+        );
+        assert_eq!(report.transition, Transition::Continue);
+
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Underwear),
+        );
+        assert_eq!(report.transition, Transition::ComboBreaker);
+        assert_eq!(state.phase, Phase::SelectElement);
+        assert_eq!(state.action, 0);
+    }
+
+    #[test]
+    fn knock_out_ends_round_then_set() {
+        let mut state = standard(2);
+        state.player_two.health = 1;
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Hand),
+        );
+        assert_eq!(
+            report.transition,
+            Transition::RoundOver(MatchResult::Winner(Player::One))
+        );
+        assert_eq!(state.phase, Phase::RoundOver);
+        assert_eq!(state.player_one.round_wins, 1);
+
+        state.next_round();
+        assert_eq!(state.round, 2);
+        assert_eq!(state.player_two.health, MAX_HEALTH);
+        assert_eq!(state.player_one.damage_dealt, 0);
+        state.player_two.health = 1;
+        let report = state.step(
+            pick(Element::Water, Action::Toilet),
+            pick(Element::Water, Action::Hand),
+        );
+        assert_eq!(report.transition, Transition::GameOver);
+        assert_eq!(state.get_result(), MatchResult::Winner(Player::One));
+    }
+
+    // Both players out at once, which only forfeited hearts can cause
+    fn double_knock_out(
+        tiebreak: TiebreakRule,
+        damage_dealt: (i32, i32),
+    ) -> (MatchState, Transition) {
+        let rules = MatchRules {
+            tiebreak,
+            ..MatchRules::standard()
+        };
+        let mut state = MatchState::new(Ruleset::standard(), rules, 1);
+        state.player_one.health = 1;
+        state.player_two.health = 1;
+        state.player_one.damage_dealt = damage_dealt.0;
+        state.player_two.damage_dealt = damage_dealt.1;
+        state.forfeit_heart(Player::One);
+        state.forfeit_heart(Player::Two);
+        let report = state.step(
+            pick(Element::Fire, Action::Toilet),
+            pick(Element::Water, Action::Hand),
+        );
+        (state, report.transition)
+    }
+
+    #[test]
+    fn sudden_death_restarts_on_one_heart() {
+        let (state, transition) = double_knock_out(TiebreakRule::SuddenDeath, (0, 0));
+        assert_eq!(transition, Transition::SuddenDeath);
+        assert_eq!(state.player_one.health, 1);
+        assert_eq!(state.player_two.health, 1);
+        assert_eq!(state.phase, Phase::SelectElement);
+    }
+
+    #[test]
+    fn most_damage_settles_double_knock_out() {
+        let (state, transition) = double_knock_out(TiebreakRule::MostDamage, (1, 3));
+        assert_eq!(transition, Transition::GameOver);
+        assert_eq!(state.get_result(), MatchResult::Winner(Player::Two));
+
+        let (state, _) = double_knock_out(TiebreakRule::MostDamage, (3, 3));
+        assert_eq!(state.get_result(), MatchResult::DoubleKo);
+    }
+
+    #[test]
+    fn declared_draw_is_a_double_knock_out() {
+        let (state, transition) = double_knock_out(TiebreakRule::DeclaredDraw, (4, 0));
+        assert_eq!(transition, Transition::GameOver);
+        assert_eq!(state.last_round, Some(MatchResult::DoubleKo));
+        assert_eq!(state.get_result(), MatchResult::DoubleKo);
+    }
+
+    #[test]
+    fn even_rounds_are_a_draw() {
+        let mut state = standard(2);
+        state.player_one.round_wins = 1;
+        state.player_two.round_wins = 1;
+        state.last_round = Some(MatchResult::Winner(Player::Two));
+        assert_eq!(state.get_result(), MatchResult::Draw);
+    }
+
+    #[test]
+    fn clamped_rules_stay_playable() {
+        let rules = MatchRules {
+            starting_health: 0,
+            countdown_time: f32::NAN,
+            reveal_time: -1.0,
+            complement_damage: 0,
+            base_damage: 3,
+            ..MatchRules::standard()
+        }
+        .clamped();
+        assert_eq!(rules.starting_health, 1);
+        assert_eq!(rules.countdown_time, COUNTDOWN_TIME);
+        assert_eq!(rules.reveal_time, 0.5);
+        assert_eq!(rules.complement_damage, 3);
+        assert_eq!(MatchRules::marathon().clamped(), MatchRules::marathon());
+    }
+
+    #[test]
+    fn forfeit_heart_costs_one_health() {
+        let mut state = standard(1);
+        state.forfeit_heart(Player::Two);
+        assert_eq!(state.player_two.health, MAX_HEALTH - 1);
+        assert_eq!(state.player_one.health, MAX_HEALTH);
+        assert!(!state.decided());
+    }
+
+    #[test]
+    fn forfeited_knock_out_skips_the_exchange() {
+        let mut state = standard(1);
+        state.player_two.health = 1;
+        state.forfeit_heart(Player::Two);
+        assert!(state.decided());
+        let report = state.step(
+            pick(Element::Water, Action::Hand),
+            pick(Element::Water, Action::Toilet),
+        );
+        assert_eq!(report.transition, Transition::GameOver);
+        assert_eq!(report.damage, 0);
+        assert_eq!(state.player_one.health, MAX_HEALTH);
+        assert_eq!(state.action, 0);
+        assert_eq!(state.get_result(), MatchResult::Winner(Player::One));
+    }
+
+    #[test]
+    fn forfeit_gives_the_set_to_the_opponent() {
+        let mut state = standard(2);
+        state.forfeit(Player::One);
+        let report = state.step(
+            pick(Element::Water, Action::Hand),
+            pick(Element::Water, Action::Toilet),
+        );
+        assert_eq!(report.transition, Transition::GameOver);
+        assert_eq!(report.damage, 0);
+        assert_eq!(state.get_result(), MatchResult::Winner(Player::Two));
+    }
+
+    #[test]
+    fn double_forfeit_is_a_double_ko() {
+        let mut state = standard(2);
+        state.player_one.round_wins = 1;
+        state.forfeit(Player::Two);
+        state.forfeit(Player::One);
+        let report = state.step(ChoiceSelection::default(), ChoiceSelection::default());
+        assert_eq!(report.transition, Transition::GameOver);
+        assert_eq!(state.last_round, Some(MatchResult::DoubleKo));
+        assert_eq!(state.get_result(), MatchResult::DoubleKo);
+    }
+}
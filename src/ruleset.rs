@@ -0,0 +1,130 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+
+use crate::{rules::Ruleset, schedule::GameSet, settings::GameVariant};
+
+const STANDARD_RULESET: &str = "rules/standard.ruleset.ron";
+const EXTENDED_RULESET: &str = "rules/extended.ruleset.ron";
+
+#[derive(Asset, TypePath, Debug)]
+pub struct RulesetAsset(pub Ruleset);
+
+#[derive(Default)]
+struct RulesetLoader;
+
+impl AssetLoader for RulesetLoader {
+    type Asset = RulesetAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(RulesetAsset(ron::de::from_bytes(&bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ruleset.ron"]
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct RulesetHandles {
+    pub standard: Handle<RulesetAsset>,
+    pub extended: Handle<RulesetAsset>,
+}
+
+impl RulesetHandles {
+    // Falls back to the built-in rules if the asset is missing or still loading
+    pub fn get(&self, variant: GameVariant, assets: &Assets<RulesetAsset>) -> Ruleset {
+        let (handle, fallback): (_, fn() -> Ruleset) = match variant {
+            GameVariant::Classic => (&self.standard, Ruleset::standard),
+            GameVariant::Extended => (&self.extended, Ruleset::extended),
+        };
+        match assets.get(handle) {
+            Some(ruleset) => ruleset.0.clone(),
+            None => {
+                warn!(
+                    "Ruleset for {:?} not loaded, using the built-in rules",
+                    variant
+                );
+                fallback()
+            }
+        }
+    }
+}
+
+pub struct RulesetPlugin;
+
+impl Plugin for RulesetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<RulesetAsset>();
+        app.init_asset_loader::<RulesetLoader>();
+        app.init_resource::<RulesetHandles>();
+        app.add_systems(PreStartup, setup.in_set(GameSet::Flow));
+    }
+}
+
+fn setup(asset_server: Res<AssetServer>, mut handles: ResMut<RulesetHandles>) {
+    handles.standard = asset_server.load(STANDARD_RULESET);
+    handles.extended = asset_server.load(EXTENDED_RULESET);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    use crate::types::Choice;
+
+    fn shipped(source: &str) -> Ruleset {
+        match ron::from_str(source) {
+            Ok(ruleset) => ruleset,
+            Err(e) => panic!("shipped ruleset doesn't load: {}", e),
+        }
+    }
+
+    #[test]
+    fn shipped_rulesets_match_the_built_in_ones() {
+        let standard = shipped(include_str!("../assets/rules/standard.ruleset.ron"));
+        let extended = shipped(include_str!("../assets/rules/extended.ruleset.ron"));
+        assert_eq!(standard, Ruleset::standard());
+        assert_eq!(extended, Ruleset::extended());
+    }
+
+    #[test]
+    fn extended_ruleset_is_balanced() {
+        let ruleset = shipped(include_str!("../assets/rules/extended.ruleset.ron"));
+        let choices: Vec<Choice> = ruleset
+            .elements()
+            .into_iter()
+            .map(Choice::Element)
+            .chain(ruleset.actions().into_iter().map(Choice::Action))
+            .collect();
+        assert_eq!(choices.len(), 10);
+        for &choice in &choices {
+            let same_kind = choices
+                .iter()
+                .filter(|other| **other != choice)
+                .filter(|other| {
+                    matches!(other, Choice::Element(_)) == matches!(choice, Choice::Element(_))
+                });
+            let wins = same_kind
+                .clone()
+                .filter(|other| ruleset.compare(choice, **other) == Ordering::Greater)
+                .count();
+            let losses = same_kind
+                .filter(|other| ruleset.compare(choice, **other) == Ordering::Less)
+                .count();
+            assert_eq!((wins, losses), (2, 2), "{:?}", choice);
+            assert_ne!(ruleset.get_complement(choice), Choice::None, "{:?}", choice);
+        }
+    }
+}
@@ -1,21 +1,184 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, PartialEq, Eq)]
+use crate::rules::MatchRules;
+use crate::types::Player;
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum GameMode {
     #[default]
     SinglePlayer,
     TwoPlayer,
+    // Choices are fed from a recorded match instead of players
+    Replay,
+    // Two bots play each other
+    BotVsBot,
+    // Against a remote player through the relay, playing the given side here
+    Online(Player),
+}
+
+impl GameMode {
+    // Whether the player's picks come from this machine's keyboard, controllers or screen
+    pub fn human(&self, player: Player) -> bool {
+        match self {
+            GameMode::SinglePlayer => player == Player::One,
+            GameMode::TwoPlayer => true,
+            GameMode::Online(side) => *side == player,
+            GameMode::Replay | GameMode::BotVsBot => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum GameVariant {
+    // Three elements and three actions
+    #[default]
+    Classic,
+    // Five elements and five actions
+    Extended,
 }
 
-#[derive(Resource, Debug, Default)]
+impl GameVariant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameVariant::Classic => "Classic Rules",
+            GameVariant::Extended => "Extended Rules",
+        }
+    }
+
+    // How many of combo::CHOICES are in play
+    pub fn choice_count(&self) -> usize {
+        match self {
+            GameVariant::Classic => 3,
+            GameVariant::Extended => 5,
+        }
+    }
+}
+
+// How many rounds make up a match
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum SetLength {
+    #[default]
+    Single,
+    BestOfThree,
+    BestOfFive,
+    BestOfSeven,
+}
+
+impl SetLength {
+    pub fn rounds_to_win(&self) -> u32 {
+        match self {
+            SetLength::Single => 1,
+            SetLength::BestOfThree => 2,
+            SetLength::BestOfFive => 3,
+            SetLength::BestOfSeven => 4,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SetLength::Single => SetLength::BestOfThree,
+            SetLength::BestOfThree => SetLength::BestOfFive,
+            SetLength::BestOfFive => SetLength::BestOfSeven,
+            SetLength::BestOfSeven => SetLength::Single,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SetLength::Single => "Single Round",
+            SetLength::BestOfThree => "Best of 3",
+            SetLength::BestOfFive => "Best of 5",
+            SetLength::BestOfSeven => "Best of 7",
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    // Learns the opponent's habits over the match
+    Adaptive,
+    // Plays the equilibrium mix, which no strategy can beat on average
+    Unexploitable,
+}
+
+impl BotDifficulty {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Normal => "Normal",
+            BotDifficulty::Hard => "Hard",
+            BotDifficulty::Adaptive => "Adaptive",
+            BotDifficulty::Unexploitable => "Unexploitable",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            BotDifficulty::Easy => BotDifficulty::Normal,
+            BotDifficulty::Normal => BotDifficulty::Hard,
+            BotDifficulty::Hard => BotDifficulty::Adaptive,
+            BotDifficulty::Adaptive => BotDifficulty::Unexploitable,
+            BotDifficulty::Unexploitable => BotDifficulty::Easy,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameSettings {
     pub game_mode: GameMode,
+    pub variant: GameVariant,
+    pub set_length: SetLength,
+    // Blue's bot, and Red's when watching CPU vs CPU
+    #[serde(default)]
+    pub difficulty: BotDifficulty,
+    #[serde(default)]
+    pub red_bot: BotDifficulty,
+    // Named opponent playing Blue in single player, in place of the difficulty
+    #[serde(default)]
+    pub opponent: Option<String>,
+    // Hide each player's picks from the other until the reveal
+    #[serde(default)]
+    pub blind_picks: bool,
+    // Fixed seed for every match, otherwise each match picks a fresh one
+    pub seed: Option<u64>,
+}
+
+impl GameSettings {
+    pub fn from_args() -> Self {
+        Self {
+            seed: parse_seed(std::env::args()),
+            ..default()
+        }
+    }
 }
 
+// Reads `--seed <n>` or `--seed=<n>` from the command line
+fn parse_seed(mut args: impl Iterator<Item = String>) -> Option<u64> {
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next().and_then(|seed| seed.parse().ok());
+        }
+        if let Some(seed) = arg.strip_prefix("--seed=") {
+            return seed.parse().ok();
+        }
+    }
+    None
+}
+
+// The rules for the next match, edited on the Custom Match screen. Kept out of
+// GameSettings so rules can be tuned without touching the mode or opponent.
+impl Resource for MatchRules {}
+
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GameSettings>();
+        app.insert_resource(GameSettings::from_args());
+        app.init_resource::<MatchRules>();
     }
 }
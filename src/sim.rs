@@ -0,0 +1,373 @@
+// Headless bot-vs-bot matches. Matches are stepped straight through MatchState,
+// so the numbers come from the same rules as the game, just without the clock.
+use std::fmt;
+
+use rand::RngCore;
+use serde::Serialize;
+
+use crate::rules::{ChoiceSelection, MatchResult, MatchRules, MatchState, Ruleset, Transition};
+use crate::settings::BotDifficulty;
+use crate::strategy::{for_difficulty, BotStrategy, MatchView};
+use crate::types::{Choice, Element, Outcome, Player};
+
+// Gives up on a match that never ends, such as two bots drawing forever
+const MAX_EXCHANGES: u32 = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub red: BotDifficulty,
+    pub blue: BotDifficulty,
+    pub ruleset: Ruleset,
+    pub rules: MatchRules,
+    pub rounds_to_win: u32,
+    pub matches: u32,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ElementStats {
+    pub picks: u32,
+    // Damage dealt by actions played on this element
+    pub damage: i32,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SimStats {
+    pub matches: u32,
+    pub red_wins: u32,
+    pub blue_wins: u32,
+    pub draws: u32,
+    pub double_kos: u32,
+    // Matches stopped at MAX_EXCHANGES, which also count as draws
+    pub unfinished: u32,
+    pub rounds: u32,
+    pub exchanges: u32,
+    pub combo_breakers: u32,
+    pub sudden_deaths: u32,
+    pub elements: Vec<(Element, ElementStats)>,
+}
+
+// A single figure in the report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Stat {
+    Label(String),
+    Count(u64),
+    Rate(f64),
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stat::Label(label) => write!(f, "{}", label),
+            Stat::Count(count) => write!(f, "{}", count),
+            Stat::Rate(rate) => write!(f, "{:.4}", rate),
+        }
+    }
+}
+
+impl SimStats {
+    fn per_match(&self, total: impl Into<f64>) -> f64 {
+        if self.matches == 0 {
+            0.0
+        } else {
+            total.into() / self.matches as f64
+        }
+    }
+
+    // Every figure in report order, shared by the text, CSV and JSON output
+    pub fn report(&self, config: &SimConfig, seed: u64) -> Vec<(String, Stat)> {
+        let mut rows = vec![
+            ("seed".to_string(), Stat::Count(seed)),
+            (
+                "red_bot".to_string(),
+                Stat::Label(config.red.label().to_string()),
+            ),
+            (
+                "blue_bot".to_string(),
+                Stat::Label(config.blue.label().to_string()),
+            ),
+            ("matches".to_string(), Stat::Count(self.matches.into())),
+            (
+                "red_win_rate".to_string(),
+                Stat::Rate(self.per_match(self.red_wins)),
+            ),
+            (
+                "blue_win_rate".to_string(),
+                Stat::Rate(self.per_match(self.blue_wins)),
+            ),
+            (
+                "draw_rate".to_string(),
+                Stat::Rate(self.per_match(self.draws)),
+            ),
+            (
+                "double_ko_rate".to_string(),
+                Stat::Rate(self.per_match(self.double_kos)),
+            ),
+            (
+                "unfinished".to_string(),
+                Stat::Count(self.unfinished.into()),
+            ),
+            (
+                "rounds_per_match".to_string(),
+                Stat::Rate(self.per_match(self.rounds)),
+            ),
+            (
+                "exchanges_per_match".to_string(),
+                Stat::Rate(self.per_match(self.exchanges)),
+            ),
+            (
+                "combo_breakers_per_match".to_string(),
+                Stat::Rate(self.per_match(self.combo_breakers)),
+            ),
+            (
+                "combo_breakers_per_exchange".to_string(),
+                Stat::Rate(if self.exchanges == 0 {
+                    0.0
+                } else {
+                    self.combo_breakers as f64 / self.exchanges as f64
+                }),
+            ),
+            (
+                "sudden_deaths_per_match".to_string(),
+                Stat::Rate(self.per_match(self.sudden_deaths)),
+            ),
+        ];
+        for (element, stats) in &self.elements {
+            let name = element.to_string().to_lowercase();
+            rows.push((format!("{}_picks", name), Stat::Count(stats.picks.into())));
+            rows.push((
+                format!("{}_damage", name),
+                Stat::Count(stats.damage.max(0) as u64),
+            ));
+            rows.push((
+                format!("{}_damage_per_pick", name),
+                Stat::Rate(if stats.picks == 0 {
+                    0.0
+                } else {
+                    stats.damage as f64 / stats.picks as f64
+                }),
+            ));
+        }
+        rows
+    }
+
+    fn element_mut(&mut self, element: Choice) -> Option<&mut ElementStats> {
+        let Choice::Element(element) = element else {
+            return None;
+        };
+        self.elements
+            .iter_mut()
+            .find(|(e, _)| *e == element)
+            .map(|(_, stats)| stats)
+    }
+}
+
+// Accepts a bot's label in any case, such as "hard" or "Unexploitable"
+pub fn parse_difficulty(name: &str) -> Option<BotDifficulty> {
+    let mut difficulty = BotDifficulty::Easy;
+    loop {
+        if difficulty.label().eq_ignore_ascii_case(name) {
+            return Some(difficulty);
+        }
+        difficulty = difficulty.next();
+        if difficulty == BotDifficulty::Easy {
+            return None;
+        }
+    }
+}
+
+pub fn run(config: &SimConfig, rng: &mut dyn RngCore) -> SimStats {
+    let mut stats = SimStats {
+        elements: config
+            .ruleset
+            .elements()
+            .into_iter()
+            .map(|element| (element, ElementStats::default()))
+            .collect(),
+        ..Default::default()
+    };
+    for _ in 0..config.matches {
+        play_match(config, rng, &mut stats);
+    }
+    stats
+}
+
+fn slot(player: Player) -> usize {
+    match player {
+        Player::One => 0,
+        Player::Two => 1,
+    }
+}
+
+fn view<'a>(
+    state: &'a MatchState,
+    picks: &[ChoiceSelection; 2],
+    player: Player,
+    hide_opponent: bool,
+) -> MatchView<'a> {
+    MatchView {
+        player,
+        state,
+        own: picks[slot(player)],
+        opponent_element: if hide_opponent {
+            Choice::None
+        } else {
+            picks[slot(player.opponent())].element
+        },
+    }
+}
+
+fn play_match(config: &SimConfig, rng: &mut dyn RngCore, stats: &mut SimStats) {
+    let mut state = MatchState::new(config.ruleset.clone(), config.rules, config.rounds_to_win);
+    let mut bots: [(Player, Box<dyn BotStrategy>); 2] = [
+        (Player::One, for_difficulty(config.red)),
+        (Player::Two, for_difficulty(config.blue)),
+    ];
+    let mut picks = [ChoiceSelection::default(); 2];
+    let mut exchanges = 0;
+    stats.matches += 1;
+
+    loop {
+        if state.action == 0 {
+            // Elements are picked without seeing each other's
+            for (player, bot) in &mut bots {
+                let element = bot.choose_element(&view(&state, &picks, *player, true), rng);
+                picks[slot(*player)].element = Choice::Element(element);
+                if let Some(element_stats) = stats.element_mut(Choice::Element(element)) {
+                    element_stats.picks += 1;
+                }
+            }
+            for (player, bot) in &mut bots {
+                if let Choice::Element(element) = picks[slot(player.opponent())].element {
+                    bot.observe_element(&view(&state, &picks, *player, false), element);
+                }
+            }
+        }
+
+        for (player, bot) in &mut bots {
+            let action = bot.choose_action(&view(&state, &picks, *player, false), rng);
+            picks[slot(*player)].action = Choice::Action(action);
+        }
+        let report = state.step(picks[0], picks[1]);
+        exchanges += 1;
+        stats.exchanges += 1;
+
+        let winner = match report.result.outcome {
+            Outcome::PlayerOne => Some(Player::One),
+            Outcome::PlayerTwo => Some(Player::Two),
+            Outcome::Draw => None,
+        };
+        if let Some(winner) = winner {
+            if let Some(element_stats) = stats.element_mut(picks[slot(winner)].element) {
+                element_stats.damage += report.damage;
+            }
+        }
+        for (player, bot) in &mut bots {
+            if let Choice::Action(action) = picks[slot(player.opponent())].action {
+                let view = view(&state, &picks, *player, false);
+                bot.observe_exchange(&view, action, report.result.outcome);
+            }
+        }
+
+        for pick in &mut picks {
+            pick.action = Choice::None;
+        }
+        match report.transition {
+            Transition::Advantage(_) | Transition::Continue => (),
+            Transition::NoAdvantage => picks = Default::default(),
+            Transition::ComboBreaker => {
+                stats.combo_breakers += 1;
+                picks = Default::default();
+            }
+            Transition::SuddenDeath => {
+                stats.sudden_deaths += 1;
+                picks = Default::default();
+            }
+            Transition::RoundOver(_) => {
+                state.next_round();
+                picks = Default::default();
+            }
+            Transition::GameOver => {
+                stats.rounds += state.round;
+                match state.get_result() {
+                    MatchResult::Winner(Player::One) => stats.red_wins += 1,
+                    MatchResult::Winner(Player::Two) => stats.blue_wins += 1,
+                    MatchResult::Draw => stats.draws += 1,
+                    MatchResult::DoubleKo => stats.double_kos += 1,
+                }
+                return;
+            }
+        }
+        if exchanges >= MAX_EXCHANGES {
+            stats.rounds += state.round;
+            stats.draws += 1;
+            stats.unfinished += 1;
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::GameRng;
+
+    fn config(matches: u32) -> SimConfig {
+        SimConfig {
+            red: BotDifficulty::Hard,
+            blue: BotDifficulty::Adaptive,
+            ruleset: Ruleset::standard(),
+            rules: MatchRules::standard(),
+            rounds_to_win: 2,
+            matches,
+        }
+    }
+
+    #[test]
+    fn totals_add_up() {
+        let config = config(200);
+        let stats = run(&config, &mut GameRng::new(42));
+        assert_eq!(stats.matches, 200);
+        assert_eq!(
+            stats.red_wins + stats.blue_wins + stats.draws + stats.double_kos,
+            stats.matches
+        );
+        assert!(stats.rounds >= stats.matches);
+        assert!(stats.exchanges >= stats.matches);
+
+        // Both players pick an element at once, at most once per exchange
+        let picks: u32 = stats.elements.iter().map(|(_, e)| e.picks).sum();
+        assert_eq!(picks % 2, 0);
+        assert!(picks >= 2 * stats.matches && picks <= 2 * stats.exchanges);
+        // Each set won took a knock-out per round, and no exchange hits harder than a double
+        let damage: i32 = stats.elements.iter().map(|(_, e)| e.damage).sum();
+        let won = (stats.red_wins + stats.blue_wins) * config.rounds_to_win;
+        assert!(damage >= won as i32 * config.rules.starting_health);
+        assert!(damage <= stats.exchanges as i32 * config.rules.complement_damage);
+    }
+
+    #[test]
+    fn same_seed_same_report() {
+        let config = config(50);
+        let first = run(&config, &mut GameRng::new(7)).report(&config, 7);
+        let second = run(&config, &mut GameRng::new(7)).report(&config, 7);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn endless_matches_are_drawn() {
+        // Nobody ever takes damage, so no match can end
+        let config = SimConfig {
+            rules: MatchRules {
+                base_damage: 0,
+                complement_damage: 0,
+                ..MatchRules::standard()
+            },
+            ..config(2)
+        };
+        let stats = run(&config, &mut GameRng::new(1));
+        assert_eq!(stats.draws, 2);
+        assert_eq!(stats.unfinished, 2);
+        assert_eq!(stats.exchanges, 2 * MAX_EXCHANGES);
+    }
+}
@@ -0,0 +1,520 @@
+// Nash equilibrium strategies for a single round. Every point of the round is a
+// simultaneous-pick zero-sum game whose payoff is Red's chance of winning the
+// round. Health only ever goes down, so states are solved from low health up,
+// iterating only over the draws that loop back to the same health.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::rules::{ChoiceSelection, MatchRules, Ruleset};
+use crate::types::{Action, Choice, Element, Player};
+
+const TOLERANCE: f64 = 1e-9;
+const MAX_ITERATIONS: usize = 10_000;
+const EPSILON: f64 = 1e-12;
+
+static LAST: Mutex<Option<Arc<Solver>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ActionKey {
+    health: (i32, i32),
+    // Indices into the solver's elements
+    elements: (usize, usize),
+    // Who is attacking in the running combo, if any
+    attacker: Option<Player>,
+}
+
+// Optimal mixes for both sides of a zero-sum matrix game
+#[derive(Debug, Clone)]
+struct MatrixSolution {
+    value: f64,
+    row: Vec<f64>,
+    column: Vec<f64>,
+}
+
+pub struct Solver {
+    ruleset: Ruleset,
+    rules: MatchRules,
+    elements: Vec<Element>,
+    actions: Vec<Action>,
+    element_values: HashMap<(i32, i32), f64>,
+    action_values: HashMap<ActionKey, f64>,
+}
+
+impl Solver {
+    pub fn new(ruleset: &Ruleset, rules: &MatchRules) -> Self {
+        let mut solver = Self {
+            ruleset: ruleset.clone(),
+            rules: *rules,
+            elements: ruleset.elements(),
+            actions: ruleset.actions(),
+            element_values: HashMap::new(),
+            action_values: HashMap::new(),
+        };
+        let max = rules.starting_health.max(1);
+        let mut levels: Vec<(i32, i32)> = (1..=max)
+            .flat_map(|h1| (1..=max).map(move |h2| (h1, h2)))
+            .collect();
+        levels.sort_by_key(|(h1, h2)| h1 + h2);
+        for health in levels {
+            solver.solve_level(health);
+        }
+        solver
+    }
+
+    // Solving takes a moment, so reuse the last solver while the rules stay the same
+    pub fn shared(ruleset: &Ruleset, rules: &MatchRules) -> Arc<Solver> {
+        if let Some(solver) = Self::cached(ruleset, rules) {
+            return solver;
+        }
+        // Solve without holding the lock, so cached() never waits on it
+        let solver = Arc::new(Solver::new(ruleset, rules));
+        *LAST.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(solver.clone());
+        solver
+    }
+
+    // The last solver, if it was made for these rules
+    pub fn cached(ruleset: &Ruleset, rules: &MatchRules) -> Option<Arc<Solver>> {
+        let last = LAST.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        last.as_ref()
+            .filter(|solver| solver.ruleset == *ruleset && solver.rules == *rules)
+            .cloned()
+    }
+
+    pub fn elements(&self) -> &[Element] {
+        &self.elements
+    }
+
+    // Red's chance of winning the round from the element phase
+    pub fn win_chance(&self, health: (i32, i32)) -> f64 {
+        self.element_value(self.clamp(health))
+    }
+
+    pub fn element_strategy(&self, player: Player, health: (i32, i32)) -> Vec<(Element, f64)> {
+        let solution = solve_matrix(&self.element_matrix(self.clamp(health)));
+        let mix = match player {
+            Player::One => solution.row,
+            Player::Two => solution.column,
+        };
+        self.elements.iter().copied().zip(mix).collect()
+    }
+
+    // None if either element is not part of the ruleset
+    pub fn action_strategy(
+        &self,
+        player: Player,
+        health: (i32, i32),
+        elements: (Element, Element),
+        attacker: Option<Player>,
+    ) -> Option<Vec<(Action, f64)>> {
+        let key = ActionKey {
+            health: self.clamp(health),
+            elements: (
+                self.elements.iter().position(|e| *e == elements.0)?,
+                self.elements.iter().position(|e| *e == elements.1)?,
+            ),
+            attacker,
+        };
+        let solution = solve_matrix(&self.action_matrix(key));
+        let mix = match player {
+            Player::One => solution.row,
+            Player::Two => solution.column,
+        };
+        Some(self.actions.iter().copied().zip(mix).collect())
+    }
+
+    fn clamp(&self, health: (i32, i32)) -> (i32, i32) {
+        let max = self.rules.starting_health.max(1);
+        (health.0.clamp(1, max), health.1.clamp(1, max))
+    }
+
+    fn solve_level(&mut self, health: (i32, i32)) {
+        let count = self.elements.len();
+        let attackers = [Some(Player::One), Some(Player::Two), None];
+        for _ in 0..MAX_ITERATIONS {
+            let mut change: f64 = 0.0;
+            for attacker in attackers {
+                for e1 in 0..count {
+                    for e2 in 0..count {
+                        let key = ActionKey {
+                            health,
+                            elements: (e1, e2),
+                            attacker,
+                        };
+                        let value = solve_matrix(&self.action_matrix(key)).value;
+                        change = change.max((value - self.action_value(key)).abs());
+                        self.action_values.insert(key, value);
+                    }
+                }
+            }
+            let value = solve_matrix(&self.element_matrix(health)).value;
+            change = change.max((value - self.element_value(health)).abs());
+            self.element_values.insert(health, value);
+            if change < TOLERANCE {
+                break;
+            }
+        }
+    }
+
+    fn element_value(&self, health: (i32, i32)) -> f64 {
+        self.element_values.get(&health).copied().unwrap_or(0.5)
+    }
+
+    fn action_value(&self, key: ActionKey) -> f64 {
+        self.action_values.get(&key).copied().unwrap_or(0.5)
+    }
+
+    fn element_matrix(&self, health: (i32, i32)) -> Vec<Vec<f64>> {
+        let count = self.elements.len();
+        (0..count)
+            .map(|e1| {
+                (0..count)
+                    .map(|e2| {
+                        self.action_value(ActionKey {
+                            health,
+                            elements: (e1, e2),
+                            attacker: None,
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn action_matrix(&self, key: ActionKey) -> Vec<Vec<f64>> {
+        self.actions
+            .iter()
+            .map(|a1| {
+                self.actions
+                    .iter()
+                    .map(|a2| self.after_exchange(key, *a1, *a2))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Mirrors MatchState::step
+    fn after_exchange(&self, key: ActionKey, a1: Action, a2: Action) -> f64 {
+        let (h1, h2) = key.health;
+        match self.ruleset.compare(Choice::Action(a1), Choice::Action(a2)) {
+            Ordering::Greater => {
+                let h2 = h2 - self.damage(self.elements[key.elements.0], a1);
+                if h2 <= 0 {
+                    return 1.0;
+                }
+                self.after_win(Player::One, key, (h1, h2))
+            }
+            Ordering::Less => {
+                let h1 = h1 - self.damage(self.elements[key.elements.1], a2);
+                if h1 <= 0 {
+                    return 0.0;
+                }
+                self.after_win(Player::Two, key, (h1, h2))
+            }
+            Ordering::Equal => match key.attacker {
+                None => self.element_value(key.health),
+                Some(_) => self.action_value(key),
+            },
+        }
+    }
+
+    fn after_win(&self, winner: Player, key: ActionKey, health: (i32, i32)) -> f64 {
+        match key.attacker {
+            // Combo breaker
+            Some(attacker) if attacker != winner => self.element_value(health),
+            _ => self.action_value(ActionKey {
+                health,
+                elements: key.elements,
+                attacker: Some(winner),
+            }),
+        }
+    }
+
+    fn damage(&self, element: Element, action: Action) -> i32 {
+        let selection = ChoiceSelection {
+            element: Choice::Element(element),
+            action: Choice::Action(action),
+        };
+        if selection.can_double(&self.ruleset) {
+            self.rules.complement_damage
+        } else {
+            self.rules.base_damage
+        }
+    }
+}
+
+// Pick from a mix, or None if it is empty
+pub fn sample<T: Copy>(mix: &[(T, f64)], rng: &mut impl Rng) -> Option<T> {
+    mix.choose_weighted(rng, |(_, odds)| odds.max(0.0))
+        .ok()
+        .map(|(pick, _)| *pick)
+}
+
+// Solves a zero-sum game for the row player with the simplex method, by
+// maximising sum(y) subject to Ay <= 1 after shifting every payoff above zero
+fn solve_matrix(matrix: &[Vec<f64>]) -> MatrixSolution {
+    let rows = matrix.len();
+    let columns = matrix.first().map_or(0, |row| row.len());
+    if rows == 0 || columns == 0 {
+        return MatrixSolution {
+            value: 0.0,
+            row: vec![],
+            column: vec![],
+        };
+    }
+    let min = matrix
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let max = matrix
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    // Every mix is optimal when nothing matters, so don't favour the first pick
+    if max - min < TOLERANCE {
+        return MatrixSolution {
+            value: min,
+            row: vec![1.0 / rows as f64; rows],
+            column: vec![1.0 / columns as f64; columns],
+        };
+    }
+    let shift = 1.0 - min;
+
+    let width = columns + rows + 1;
+    let mut tableau: Vec<Vec<f64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![0.0; width];
+            for (j, payoff) in row.iter().enumerate() {
+                line[j] = payoff + shift;
+            }
+            line[columns + i] = 1.0;
+            line[width - 1] = 1.0;
+            line
+        })
+        .collect();
+    let mut objective = vec![0.0; width];
+    objective[..columns].iter_mut().for_each(|c| *c = -1.0);
+    let mut basis: Vec<usize> = (columns..columns + rows).collect();
+
+    // Bland's rule keeps degenerate games from cycling
+    while let Some(entering) = (0..width - 1).find(|&c| objective[c] < -EPSILON) {
+        let Some(leaving) = (0..rows)
+            .filter(|&r| tableau[r][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio_a = tableau[a][width - 1] / tableau[a][entering];
+                let ratio_b = tableau[b][width - 1] / tableau[b][entering];
+                ratio_a
+                    .partial_cmp(&ratio_b)
+                    .unwrap_or(Ordering::Equal)
+                    .then(basis[a].cmp(&basis[b]))
+            })
+        else {
+            break;
+        };
+
+        let pivot = tableau[leaving][entering];
+        tableau[leaving].iter_mut().for_each(|x| *x /= pivot);
+        let pivot_row = tableau[leaving].clone();
+        for (r, line) in tableau.iter_mut().enumerate() {
+            if r != leaving {
+                let factor = line[entering];
+                line.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(x, p)| *x -= factor * p);
+            }
+        }
+        let factor = objective[entering];
+        objective
+            .iter_mut()
+            .zip(&pivot_row)
+            .for_each(|(x, p)| *x -= factor * p);
+        basis[leaving] = entering;
+    }
+
+    let total = objective[width - 1];
+    let mut column = vec![0.0; columns];
+    for (r, &variable) in basis.iter().enumerate() {
+        if variable < columns {
+            column[variable] = tableau[r][width - 1] / total;
+        }
+    }
+    let row = (0..rows).map(|i| objective[columns + i] / total).collect();
+    MatrixSolution {
+        value: 1.0 / total - shift,
+        row,
+        column,
+    }
+}
+
+// `solve [--extended] [--health N] [--base N] [--double N]`
+pub fn run_cli(mut args: impl Iterator<Item = String>) {
+    let mut ruleset = Ruleset::standard();
+    let mut rules = MatchRules::standard();
+    while let Some(arg) = args.next() {
+        let mut number = || args.next().and_then(|value| value.parse().ok());
+        match arg.as_str() {
+            "--extended" => ruleset = Ruleset::extended(),
+            "--health" => rules.starting_health = number().unwrap_or(rules.starting_health),
+            "--base" => rules.base_damage = number().unwrap_or(rules.base_damage),
+            "--double" => rules.complement_damage = number().unwrap_or(rules.complement_damage),
+            _ => eprintln!("Ignoring unknown argument {}", arg),
+        }
+    }
+    print_strategy_table(&Solver::new(&ruleset, &rules), &rules);
+}
+
+fn format_mix<T: std::fmt::Display>(mix: &[(T, f64)]) -> String {
+    mix.iter()
+        .map(|(pick, odds)| format!("{} {:>5.1}%", pick, odds * 100.0))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn print_strategy_table(solver: &Solver, rules: &MatchRules) {
+    let max = rules.starting_health.max(1);
+    println!(
+        "Equilibrium strategies for one round: {} health, {} base damage, {} double damage",
+        max, rules.base_damage, rules.complement_damage
+    );
+    println!(
+        "Red wins {:.1}% of rounds from full health\n",
+        solver.win_chance((max, max)) * 100.0
+    );
+
+    println!("Element phase (Red health / Blue health: Red's mix | Blue's mix | Red wins)");
+    let count = solver.elements().len();
+    // States in which each element beats, or loses to, every alternative
+    let mut dominant = vec![0; count];
+    let mut dominated = vec![0; count];
+    for h1 in (1..=max).rev() {
+        for h2 in (1..=max).rev() {
+            let red = solver.element_strategy(Player::One, (h1, h2));
+            let blue = solver.element_strategy(Player::Two, (h1, h2));
+            let matrix = solver.element_matrix((h1, h2));
+            // Blue's payoffs, from Blue's side of the table
+            let transposed: Vec<Vec<f64>> = (0..count)
+                .map(|j| matrix.iter().map(|row| 1.0 - row[j]).collect())
+                .collect();
+            for payoffs in [&matrix, &transposed] {
+                for element in 0..count {
+                    let others = (0..count).filter(|other| *other != element);
+                    if others
+                        .clone()
+                        .all(|other| dominates(&payoffs[element], &payoffs[other]))
+                    {
+                        dominant[element] += 1;
+                    }
+                    if others
+                        .clone()
+                        .any(|other| dominates(&payoffs[other], &payoffs[element]))
+                    {
+                        dominated[element] += 1;
+                    }
+                }
+            }
+            println!(
+                "{:>2}/{:<2} {} | {} | {:>5.1}%",
+                h1,
+                h2,
+                format_mix(&red),
+                format_mix(&blue),
+                solver.win_chance((h1, h2)) * 100.0
+            );
+        }
+    }
+
+    println!("\nOpening action mix at full health (Red's mix | Blue's mix)");
+    for e1 in solver.elements() {
+        for e2 in solver.elements() {
+            let red = solver.action_strategy(Player::One, (max, max), (*e1, *e2), None);
+            let blue = solver.action_strategy(Player::Two, (max, max), (*e1, *e2), None);
+            if let (Some(red), Some(blue)) = (red, blue) {
+                println!(
+                    "{:>5} vs {:<5} {} | {}",
+                    e1,
+                    e2,
+                    format_mix(&red),
+                    format_mix(&blue)
+                );
+            }
+        }
+    }
+
+    println!();
+    let states = 2 * max * max;
+    let mut any = false;
+    for (index, element) in solver.elements().iter().enumerate() {
+        if dominant[index] > 0 {
+            any = true;
+            println!(
+                "{} is dominant in {} of {} states",
+                element, dominant[index], states
+            );
+        }
+        if dominated[index] > 0 {
+            any = true;
+            println!(
+                "{} is dominated in {} of {} states",
+                element, dominated[index], states
+            );
+        }
+    }
+    if !any {
+        println!("No element is dominant or dominated");
+    }
+}
+
+// Whether one pick does at least as well as another against everything, and
+// strictly better against something
+fn dominates(pick: &[f64], other: &[f64]) -> bool {
+    pick.iter().zip(other).all(|(a, b)| *a >= *b - TOLERANCE)
+        && pick.iter().zip(other).any(|(a, b)| *a > *b + TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn rock_paper_scissors_is_uniform() {
+        let matrix = vec![
+            vec![0.0, -1.0, 1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![-1.0, 1.0, 0.0],
+        ];
+        let solution = solve_matrix(&matrix);
+        assert_close(solution.value, 0.0);
+        for odds in solution.row.iter().chain(&solution.column) {
+            assert_close(*odds, 1.0 / 3.0);
+        }
+    }
+
+    #[test]
+    fn dominated_row_is_never_played() {
+        let matrix = vec![vec![0.5, 0.8], vec![0.2, 0.1]];
+        let solution = solve_matrix(&matrix);
+        assert_close(solution.value, 0.5);
+        assert_close(solution.row[0], 1.0);
+        assert_close(solution.column[0], 1.0);
+    }
+
+    #[test]
+    fn mirror_match_is_even() {
+        let solver = Solver::new(&Ruleset::standard(), &MatchRules::quick());
+        let health = MatchRules::quick().starting_health;
+        assert_close(solver.win_chance((health, health)), 0.5);
+        let mix = solver.element_strategy(Player::One, (health, health));
+        assert_close(mix.iter().map(|(_, odds)| odds).sum(), 1.0);
+    }
+}
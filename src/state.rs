@@ -5,8 +5,18 @@ pub enum GameState {
     #[default]
     None,
     Title,
+    // Menu for editing the match rules
+    CustomMatch,
+    // Menu for rebinding each player's keys and buttons
+    Controls,
+    // Finding or hosting a game on the local network
+    LanGames,
+    // Waiting in a relay room for an online opponent
+    OnlineLobby,
 
     GameStart,
+    // Intro before every round after the first
+    RoundStart,
     SelectElement,
     SelectAction,
     ResolveAction,
@@ -0,0 +1,314 @@
+// Bot decision making. Strategies only see a MatchView, so they can be swapped
+// without touching the flow code and run without Bevy.
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+
+use crate::habits::HabitModel;
+use crate::rules::{ChoiceSelection, MatchState, PlayerState, Ruleset};
+use crate::settings::BotDifficulty;
+use crate::solver::{sample, Solver};
+use crate::types::{Action, Choice, Element, Outcome, Player};
+
+// How often Hard plays its best pick rather than a weighted guess
+const HARD_FOCUS: f64 = 0.8;
+// Swing in value for knocking out, or being knocked out
+const LETHAL_VALUE: f32 = 10.0;
+// Swing in value for breaking, or losing, a combo
+const COMBO_VALUE: f32 = 1.0;
+// How often Adaptive ignores what it has learned and picks at random
+const ADAPTIVE_EXPLORE: f64 = 0.15;
+
+// Everything a bot is allowed to know when it picks
+#[derive(Debug, Clone, Copy)]
+pub struct MatchView<'a> {
+    // The side the bot is playing
+    pub player: Player,
+    pub state: &'a MatchState,
+    pub own: ChoiceSelection,
+    // Choice::None while the opponent's element is hidden
+    pub opponent_element: Choice,
+}
+
+impl MatchView<'_> {
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.state.ruleset
+    }
+
+    pub fn own_state(&self) -> PlayerState {
+        match self.player {
+            Player::One => self.state.player_one,
+            Player::Two => self.state.player_two,
+        }
+    }
+
+    pub fn opponent_state(&self) -> PlayerState {
+        match self.player {
+            Player::One => self.state.player_two,
+            Player::Two => self.state.player_one,
+        }
+    }
+
+    // Whether a combo is running, and who is attacking in it
+    pub fn attacker(&self) -> Option<Player> {
+        (self.state.action > 0).then_some(self.state.advantage)
+    }
+}
+
+pub trait BotStrategy: Send + Sync {
+    fn choose_element(&mut self, view: &MatchView, rng: &mut dyn RngCore) -> Element;
+
+    fn choose_action(&mut self, view: &MatchView, rng: &mut dyn RngCore) -> Action;
+
+    // Called once the opponent's element is revealed
+    fn observe_element(&mut self, _view: &MatchView, _element: Element) {}
+
+    // Called after each exchange, with the state already updated
+    fn observe_exchange(&mut self, _view: &MatchView, _action: Action, _outcome: Outcome) {}
+}
+
+pub fn for_difficulty(difficulty: BotDifficulty) -> Box<dyn BotStrategy> {
+    match difficulty {
+        BotDifficulty::Easy => Box::new(RandomBot),
+        BotDifficulty::Normal => Box::new(WeightedBot),
+        BotDifficulty::Hard => Box::new(TacticalBot),
+        BotDifficulty::Adaptive => Box::<AdaptiveBot>::default(),
+        BotDifficulty::Unexploitable => Box::<UnexploitableBot>::default(),
+    }
+}
+
+// Uniformly random picks
+pub struct RandomBot;
+
+impl BotStrategy for RandomBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        view.ruleset().random_element(&mut rng)
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        view.ruleset().random_action(&mut rng)
+    }
+}
+
+// Random picks, leaning towards the action doubled by its element
+pub struct WeightedBot;
+
+impl BotStrategy for WeightedBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        view.ruleset().random_element(&mut rng)
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        view.ruleset().weighted_action(view.own.element, &mut rng)
+    }
+}
+
+// Weighs health, the advantage and double damage
+pub struct TacticalBot;
+
+impl BotStrategy for TacticalBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        if !rng.gen_bool(HARD_FOCUS) {
+            return view.ruleset().random_element(&mut rng);
+        }
+        best_element(view).unwrap_or_else(|| view.ruleset().random_element(&mut rng))
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        if !rng.gen_bool(HARD_FOCUS) {
+            return view.ruleset().weighted_action(view.own.element, &mut rng);
+        }
+        best_action(view, view.own.element)
+            .unwrap_or_else(|| view.ruleset().weighted_action(view.own.element, &mut rng))
+    }
+}
+
+// The element whose doubled action does best against the opponent's likely action
+fn best_element(view: &MatchView) -> Option<Element> {
+    let value = |element: Element| {
+        let element = Choice::Element(element);
+        match view.ruleset().get_complement(element) {
+            Choice::Action(action) => action_value(view, element, action),
+            _ => 0.0,
+        }
+    };
+    view.ruleset()
+        .elements()
+        .into_iter()
+        .max_by(|a, b| value(*a).partial_cmp(&value(*b)).unwrap_or(Ordering::Equal))
+}
+
+fn best_action(view: &MatchView, own_element: Choice) -> Option<Action> {
+    let value = |action| action_value(view, own_element, action);
+    view.ruleset()
+        .actions()
+        .into_iter()
+        .max_by(|a, b| value(*a).partial_cmp(&value(*b)).unwrap_or(Ordering::Equal))
+}
+
+// Expected swing in health from playing an action, assuming the opponent
+// favours the action doubled by their element like the Normal bot does
+fn action_value(view: &MatchView, own_element: Choice, action: Action) -> f32 {
+    let ruleset = view.ruleset();
+    let rules = &view.state.rules;
+    let (own, opponent) = (view.own_state(), view.opponent_state());
+    let complement = ruleset.get_complement(view.opponent_element);
+    let attacker = view.attacker();
+    let damage = |element: Choice, action: Action| {
+        let selection = ChoiceSelection {
+            element,
+            action: Choice::Action(action),
+        };
+        if selection.can_double(ruleset) {
+            rules.complement_damage
+        } else {
+            rules.base_damage
+        }
+    };
+
+    let mut total_weight = 0.0;
+    let mut value = 0.0;
+    for opponent_action in ruleset.actions() {
+        let weight = if Choice::Action(opponent_action) == complement {
+            2.0
+        } else {
+            1.0
+        };
+        let outcome = ruleset.compare(Choice::Action(action), Choice::Action(opponent_action));
+        let swing = match outcome {
+            Ordering::Greater => {
+                let dealt = damage(own_element, action);
+                let mut swing = dealt as f32;
+                if dealt >= opponent.health {
+                    swing += LETHAL_VALUE;
+                }
+                if attacker == Some(view.player.opponent()) {
+                    swing += COMBO_VALUE;
+                }
+                swing
+            }
+            Ordering::Less => {
+                let taken = damage(view.opponent_element, opponent_action);
+                let mut swing = -(taken as f32);
+                if taken >= own.health {
+                    swing -= LETHAL_VALUE;
+                }
+                if attacker == Some(view.player) {
+                    swing -= COMBO_VALUE;
+                }
+                swing
+            }
+            Ordering::Equal => 0.0,
+        };
+        total_weight += weight;
+        value += weight * swing;
+    }
+    if total_weight > 0.0 {
+        value / total_weight
+    } else {
+        0.0
+    }
+}
+
+// Learns the opponent's habits over the match and counters them
+#[derive(Default)]
+pub struct AdaptiveBot {
+    habits: HabitModel,
+}
+
+impl BotStrategy for AdaptiveBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        if rng.gen_bool(ADAPTIVE_EXPLORE) {
+            return view.ruleset().random_element(&mut rng);
+        }
+        self.habits
+            .counter_element(view.ruleset())
+            .unwrap_or_else(|| view.ruleset().random_element(&mut rng))
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        if rng.gen_bool(ADAPTIVE_EXPLORE) {
+            return view.ruleset().weighted_action(view.own.element, &mut rng);
+        }
+        self.habits
+            .counter_action(view.ruleset())
+            .unwrap_or_else(|| view.ruleset().weighted_action(view.own.element, &mut rng))
+    }
+
+    fn observe_element(&mut self, _view: &MatchView, element: Element) {
+        self.habits.observe_element(element);
+    }
+
+    fn observe_exchange(&mut self, view: &MatchView, action: Action, outcome: Outcome) {
+        let opponent = view.player.opponent();
+        let holds_advantage = view.attacker() == Some(opponent);
+        self.habits
+            .observe_exchange(opponent, action, outcome, holds_advantage);
+    }
+}
+
+// Samples the Nash equilibrium for the current state of the round
+#[derive(Default)]
+pub struct UnexploitableBot {
+    // Fetched on the first pick, once the ruleset and rules are known
+    solver: Option<Arc<Solver>>,
+    // Only use a solver someone else has already cached, rather than solving mid-pick
+    precomputed: bool,
+}
+
+impl UnexploitableBot {
+    // For the game, which solves in the background at the start of the match
+    // and plays like Normal until the solver is ready
+    pub fn precomputed() -> Self {
+        Self {
+            solver: None,
+            precomputed: true,
+        }
+    }
+
+    fn solver(&mut self, view: &MatchView) -> Option<&Solver> {
+        if self.solver.is_none() {
+            self.solver = if self.precomputed {
+                Solver::cached(view.ruleset(), &view.state.rules)
+            } else {
+                Some(Solver::shared(view.ruleset(), &view.state.rules))
+            };
+        }
+        self.solver.as_deref()
+    }
+}
+
+fn health(view: &MatchView) -> (i32, i32) {
+    (view.state.player_one.health, view.state.player_two.health)
+}
+
+impl BotStrategy for UnexploitableBot {
+    fn choose_element(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Element {
+        self.solver(view)
+            .and_then(|solver| {
+                sample(
+                    &solver.element_strategy(view.player, health(view)),
+                    &mut rng,
+                )
+            })
+            .unwrap_or_else(|| view.ruleset().random_element(&mut rng))
+    }
+
+    fn choose_action(&mut self, view: &MatchView, mut rng: &mut dyn RngCore) -> Action {
+        let mine = view.own.element;
+        let elements = match (view.player, mine, view.opponent_element) {
+            (Player::One, Choice::Element(own), Choice::Element(other)) => Some((own, other)),
+            (Player::Two, Choice::Element(own), Choice::Element(other)) => Some((other, own)),
+            _ => None,
+        };
+        let attacker = view.attacker();
+        elements
+            .and_then(|elements| {
+                self.solver(view)?
+                    .action_strategy(view.player, health(view), elements, attacker)
+            })
+            .and_then(|mix| sample(&mix, &mut rng))
+            .unwrap_or_else(|| view.ruleset().weighted_action(mine, &mut rng))
+    }
+}
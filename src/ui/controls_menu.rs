@@ -0,0 +1,402 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use crate::bindings::{
+    bindable_button, bindable_key, pad_label, slot_name, BindConflict, Bindings, KeyBinding,
+    PAD_BUTTONS, SLOTS,
+};
+use crate::combo::CHOICES;
+use crate::config::{SIZE_M, SIZE_S, SIZE_XL};
+use crate::gamepad::PadNavigation;
+use crate::globals::UiAssets;
+use crate::helper::{despawn, handle_buttons, spawn_button};
+use crate::schedule::GameSet;
+use crate::state::GameState;
+use crate::types::{Choice, Player};
+
+const ICON_SIZE: f32 = 40.0;
+const HINT: &str = "Pick a binding, then press the new key or button";
+
+#[derive(Component, Debug)]
+struct ControlsMenu;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+#[derive(Component, Debug)]
+struct ResetButton;
+
+// Which choice wins while several are held
+#[derive(Component, Debug)]
+struct PriorityButton;
+
+#[derive(Component, Debug)]
+struct MessageText;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum BindingKind {
+    Key,
+    // One of the controller buttons for the slot, by column
+    Pad(usize),
+}
+
+// One of a player's keys or buttons, waiting to be rebound when pressed
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq)]
+struct BindingButton {
+    player: Player,
+    slot: usize,
+    kind: BindingKind,
+}
+
+// The binding waiting for a new key or button, if any
+#[derive(Resource, Debug, Default)]
+struct Rebinding(Option<BindingButton>);
+
+pub struct ControlsMenuPlugin;
+
+impl Plugin for ControlsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>();
+        app.add_systems(
+            OnEnter(GameState::Controls),
+            spawn_controls_menu.in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            Update,
+            (
+                handle_buttons,
+                capture_binding,
+                handle_binding_buttons,
+                handle_reset_button,
+                handle_priority_button,
+                handle_back_button,
+                update_labels,
+            )
+                .chain()
+                .in_set(GameSet::Ui)
+                .run_if(in_state(GameState::Controls)),
+        );
+        app.add_systems(
+            OnExit(GameState::Controls),
+            (despawn::<ControlsMenu>, stop_rebinding),
+        );
+    }
+}
+
+fn side_name(player: Player) -> &'static str {
+    match player {
+        Player::One => "Red",
+        Player::Two => "Blue",
+    }
+}
+
+fn binding_label(bindings: &Bindings, button: &BindingButton) -> String {
+    let player = bindings.player(button.player);
+    match button.kind {
+        BindingKind::Key => player.keys[button.slot].label.clone(),
+        BindingKind::Pad(column) => pad_label(player.pad[button.slot][column]),
+    }
+}
+
+fn spawn_controls_menu(mut commands: Commands, ui_assets: Res<UiAssets>, bindings: Res<Bindings>) {
+    let text_font = |font_size| TextFont {
+        font: ui_assets.ms_pain.clone(),
+        font_size,
+        ..default()
+    };
+    // Spawn the Root Node
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            ControlsMenu,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn((Text::new("Controls"), text_font(SIZE_XL), TextColor::BLACK));
+            // One row per choice and one for locking in, Red's bindings on the left and Blue's on the right
+            for slot in 0..SLOTS {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        let spawn_binding = |parent: &mut ChildBuilder, player, kind| {
+                            let button = BindingButton { player, slot, kind };
+                            let label = binding_label(&bindings, &button);
+                            spawn_button(parent, button, &label, &ui_assets);
+                        };
+                        spawn_binding(parent, Player::One, BindingKind::Key);
+                        for column in 0..PAD_BUTTONS {
+                            spawn_binding(parent, Player::One, BindingKind::Pad(column));
+                        }
+                        match CHOICES.get(slot) {
+                            Some(&(element, action)) => {
+                                for choice in [Choice::Element(element), Choice::Action(action)] {
+                                    parent.spawn((
+                                        ImageNode::new(ui_assets.get_icon(choice)),
+                                        Node {
+                                            width: Val::Px(ICON_SIZE),
+                                            height: Val::Px(ICON_SIZE),
+                                            ..default()
+                                        },
+                                    ));
+                                }
+                            }
+                            None => {
+                                parent.spawn((
+                                    Text::new(slot_name(slot)),
+                                    text_font(SIZE_S),
+                                    TextColor::BLACK,
+                                    TextLayout::new_with_justify(JustifyText::Center),
+                                    Node {
+                                        width: Val::Px(ICON_SIZE * 2.0 + 8.0),
+                                        ..default()
+                                    },
+                                ));
+                            }
+                        }
+                        spawn_binding(parent, Player::Two, BindingKind::Key);
+                        for column in 0..PAD_BUTTONS {
+                            spawn_binding(parent, Player::Two, BindingKind::Pad(column));
+                        }
+                    });
+            }
+            parent.spawn((
+                Text::new(
+                    "Red on the left, Blue on the right. Metal and Earth are only used by Extended rules.",
+                ),
+                text_font(SIZE_S),
+                TextColor::BLACK,
+            ));
+            parent.spawn((
+                MessageText,
+                Text::new(HINT),
+                text_font(SIZE_M),
+                TextColor::BLACK,
+            ));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        PriorityButton,
+                        bindings.priority.label(),
+                        &ui_assets,
+                    );
+                    spawn_button(parent, ResetButton, "Defaults", &ui_assets);
+                    spawn_button(parent, BackButton, "Back", &ui_assets);
+                });
+        });
+}
+
+// Takes the next key or button for the binding that is waiting, if any
+fn capture_binding(
+    mut reader: EventReader<KeyboardInput>,
+    pads: Query<&Gamepad>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+    mut navigation: ResMut<PadNavigation>,
+    mut message: Query<&mut Text, With<MessageText>>,
+) {
+    let Some(waiting) = rebinding.0 else {
+        reader.clear();
+        return;
+    };
+    let Ok(mut message) = message.get_single_mut() else {
+        return;
+    };
+    let bound = |label: &str| {
+        format!(
+            "{} is now {}'s {}",
+            label,
+            side_name(waiting.player),
+            slot_name(waiting.slot)
+        )
+    };
+
+    // The message to finish with, once the binding has been changed or cancelled
+    let mut finished = None;
+    for event in reader.read() {
+        if event.state != ButtonState::Pressed || event.repeat || finished.is_some() {
+            continue;
+        }
+        if event.key_code == KeyCode::Escape {
+            finished = Some(HINT.to_string());
+            continue;
+        }
+        if waiting.kind != BindingKind::Key {
+            continue;
+        }
+        if !bindable_key(event.key_code) {
+            **message = "That key can't be bound, try another".to_string();
+            continue;
+        }
+        // Label the key with what the keyboard layout prints on it
+        let binding = match &event.logical_key {
+            Key::Character(character) => KeyBinding {
+                key: event.key_code,
+                label: character.to_uppercase(),
+            },
+            _ => KeyBinding::new(event.key_code),
+        };
+        let label = binding.label.clone();
+        match bindings.bind_key(waiting.player, waiting.slot, binding) {
+            Ok(()) => {
+                bindings.save();
+                finished = Some(bound(&label));
+            }
+            Err(BindConflict::OtherPlayer(other, slot) | BindConflict::SamePlayer(other, slot)) => {
+                **message = format!(
+                    "{} is already {}'s {} key, try another",
+                    label,
+                    side_name(other),
+                    slot_name(slot)
+                );
+            }
+        }
+    }
+
+    if let (None, BindingKind::Pad(column)) = (&finished, waiting.kind) {
+        let pressed = pads.iter().find_map(|pad| {
+            pad.get_just_pressed()
+                .copied()
+                .find(|button| bindable_button(*button))
+        });
+        if let Some(button) = pressed {
+            bindings.bind_button(waiting.player, waiting.slot, column, button);
+            bindings.save();
+            finished = Some(bound(&pad_label(button)));
+        }
+    }
+
+    if let Some(text) = finished {
+        rebinding.0 = None;
+        navigation.paused = false;
+        **message = text;
+    }
+}
+
+fn handle_binding_buttons(
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut navigation: ResMut<PadNavigation>,
+    mut message: Query<&mut Text, With<MessageText>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        rebinding.0 = Some(*button);
+        // Otherwise the d-pad and South would move around the menu instead
+        navigation.paused = button.kind != BindingKind::Key;
+        let input = match button.kind {
+            BindingKind::Key => "a key",
+            BindingKind::Pad(_) => "a controller button",
+        };
+        if let Ok(mut text) = message.get_single_mut() {
+            **text = format!(
+                "Press {} for {}'s {} (Esc to cancel)",
+                input,
+                side_name(button.player),
+                slot_name(button.slot)
+            );
+        }
+    }
+}
+
+fn handle_reset_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ResetButton>)>,
+    mut bindings: ResMut<Bindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut message: Query<&mut Text, With<MessageText>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        rebinding.0 = None;
+        *bindings = Bindings::default();
+        bindings.save();
+        if let Ok(mut text) = message.get_single_mut() {
+            **text = "Controls reset to the defaults".to_string();
+        }
+    }
+}
+
+fn handle_priority_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PriorityButton>)>,
+    mut bindings: ResMut<Bindings>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        bindings.priority = bindings.priority.next();
+        bindings.save();
+    }
+}
+
+fn handle_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::Title);
+    }
+}
+
+fn stop_rebinding(mut rebinding: ResMut<Rebinding>, mut navigation: ResMut<PadNavigation>) {
+    rebinding.0 = None;
+    navigation.paused = false;
+}
+
+fn update_labels(
+    bindings: Res<Bindings>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&BindingButton, &Children)>,
+    priority_query: Query<&Children, With<PriorityButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (button, children) in &button_query {
+        let label = if rebinding.0 == Some(*button) {
+            "...".to_string()
+        } else {
+            binding_label(&bindings, button)
+        };
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = label.clone();
+            }
+        }
+    }
+    for children in &priority_query {
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = bindings.priority.label().to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+
+use crate::config::{
+    BORDER_RADIUS, BUTTON_BORDER, BUTTON_HEIGHT, NORMAL_BUTTON, SIZE_M, SIZE_S, SIZE_XL,
+};
+use crate::globals::UiAssets;
+use crate::helper::{despawn, handle_buttons, spawn_button};
+use crate::rules::MatchRules;
+use crate::schedule::GameSet;
+use crate::state::GameState;
+
+const LABEL_WIDTH: Val = Val::Px(300.0);
+const VALUE_WIDTH: Val = Val::Px(220.0);
+const STEP_BUTTON_WIDTH: Val = Val::Px(50.0);
+
+#[derive(Component, Debug)]
+struct CustomMatchMenu;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+#[derive(Component, Debug)]
+struct PresetText;
+
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq)]
+enum RuleField {
+    StartingHealth,
+    CountdownTime,
+    RevealTime,
+    ComplementDamage,
+    BaseDamage,
+    Tiebreak,
+    Timeout,
+}
+
+impl RuleField {
+    const ALL: [RuleField; 7] = [
+        RuleField::StartingHealth,
+        RuleField::CountdownTime,
+        RuleField::RevealTime,
+        RuleField::ComplementDamage,
+        RuleField::BaseDamage,
+        RuleField::Tiebreak,
+        RuleField::Timeout,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            RuleField::StartingHealth => "Starting Health",
+            RuleField::CountdownTime => "Selection Time",
+            RuleField::RevealTime => "Reveal Time",
+            RuleField::ComplementDamage => "Double Damage",
+            RuleField::BaseDamage => "Base Damage",
+            RuleField::Tiebreak => "Double KO",
+            RuleField::Timeout => "No Pick",
+        }
+    }
+
+    fn value(&self, rules: &MatchRules) -> String {
+        match self {
+            RuleField::StartingHealth => rules.starting_health.to_string(),
+            RuleField::CountdownTime => format!("{:.1}s", rules.countdown_time),
+            RuleField::RevealTime => format!("{:.1}s", rules.reveal_time),
+            RuleField::ComplementDamage => rules.complement_damage.to_string(),
+            RuleField::BaseDamage => rules.base_damage.to_string(),
+            RuleField::Tiebreak => rules.tiebreak.label().to_string(),
+            RuleField::Timeout => rules.timeout.label().to_string(),
+        }
+    }
+
+    // Nudge the field up or down by one step, keeping it in a playable range
+    fn step(&self, rules: &mut MatchRules, direction: i32) {
+        match self {
+            RuleField::StartingHealth => rules.starting_health += direction,
+            RuleField::CountdownTime => rules.countdown_time += 0.5 * direction as f32,
+            RuleField::RevealTime => rules.reveal_time += 0.5 * direction as f32,
+            RuleField::ComplementDamage => rules.complement_damage += direction,
+            // Raising the base damage drags the doubled damage up with it
+            RuleField::BaseDamage => rules.base_damage += direction,
+            RuleField::Tiebreak => {
+                rules.tiebreak = if direction < 0 {
+                    rules.tiebreak.previous()
+                } else {
+                    rules.tiebreak.next()
+                }
+            }
+            RuleField::Timeout => {
+                rules.timeout = if direction < 0 {
+                    rules.timeout.previous()
+                } else {
+                    rules.timeout.next()
+                }
+            }
+        }
+        *rules = rules.clamped();
+    }
+}
+
+#[derive(Component, Debug)]
+struct StepButton {
+    field: RuleField,
+    direction: i32,
+}
+
+#[derive(Component, Debug)]
+struct RuleValue(RuleField);
+
+#[derive(Component, Debug, Copy, Clone)]
+enum PresetButton {
+    Quick,
+    Standard,
+    Marathon,
+}
+
+impl PresetButton {
+    fn rules(&self) -> MatchRules {
+        match self {
+            PresetButton::Quick => MatchRules::quick(),
+            PresetButton::Standard => MatchRules::standard(),
+            PresetButton::Marathon => MatchRules::marathon(),
+        }
+    }
+}
+
+pub struct CustomMatchPlugin;
+
+impl Plugin for CustomMatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::CustomMatch),
+            spawn_custom_match_menu.in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            Update,
+            (
+                handle_buttons,
+                handle_step_buttons,
+                handle_preset_buttons,
+                handle_back_button,
+                update_values,
+            )
+                .chain()
+                .in_set(GameSet::Ui)
+                .run_if(in_state(GameState::CustomMatch)),
+        );
+        app.add_systems(OnExit(GameState::CustomMatch), despawn::<CustomMatchMenu>);
+    }
+}
+
+fn spawn_custom_match_menu(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    rules: Res<MatchRules>,
+) {
+    // Spawn the Root Node
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            CustomMatchMenu,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn((
+                Text::new("Custom Match"),
+                TextFont {
+                    font: ui_assets.ms_pain.clone(),
+                    font_size: SIZE_XL,
+                    ..default()
+                },
+                TextColor::BLACK,
+            ));
+            parent.spawn((
+                PresetText,
+                Text::new(preset_label(&rules)),
+                TextFont {
+                    font: ui_assets.ms_pain.clone(),
+                    font_size: SIZE_S,
+                    ..default()
+                },
+                TextColor::BLACK,
+            ));
+            // One row per rule
+            for field in RuleField::ALL {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(field.label()),
+                            TextFont {
+                                font: ui_assets.ms_pain.clone(),
+                                font_size: SIZE_M,
+                                ..default()
+                            },
+                            TextColor::BLACK,
+                            Node {
+                                width: LABEL_WIDTH,
+                                ..default()
+                            },
+                        ));
+                        spawn_step_button(parent, field, -1, &ui_assets);
+                        parent.spawn((
+                            RuleValue(field),
+                            Text::new(field.value(&rules)),
+                            TextFont {
+                                font: ui_assets.ms_pain.clone(),
+                                font_size: SIZE_M,
+                                ..default()
+                            },
+                            TextColor::BLACK,
+                            TextLayout::new_with_justify(JustifyText::Center),
+                            Node {
+                                width: VALUE_WIDTH,
+                                ..default()
+                            },
+                        ));
+                        spawn_step_button(parent, field, 1, &ui_assets);
+                    });
+            }
+            // Presets
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, PresetButton::Quick, "Quick", &ui_assets);
+                    spawn_button(parent, PresetButton::Standard, "Standard", &ui_assets);
+                    spawn_button(parent, PresetButton::Marathon, "Marathon", &ui_assets);
+                });
+            spawn_button(parent, BackButton, "Back", &ui_assets);
+        });
+}
+
+fn spawn_step_button(
+    parent: &mut ChildBuilder,
+    field: RuleField,
+    direction: i32,
+    ui_assets: &UiAssets,
+) {
+    parent
+        .spawn((
+            StepButton { field, direction },
+            Button,
+            Node {
+                width: STEP_BUTTON_WIDTH,
+                height: BUTTON_HEIGHT,
+                border: BUTTON_BORDER,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BorderRadius::all(BORDER_RADIUS),
+            BackgroundColor(NORMAL_BUTTON),
+        ))
+        .with_child((
+            Text::new(if direction < 0 { "-" } else { "+" }),
+            TextFont {
+                font: ui_assets.ms_pain.clone(),
+                font_size: SIZE_M,
+                ..default()
+            },
+            TextColor::WHITE,
+        ));
+}
+
+fn preset_label(rules: &MatchRules) -> String {
+    format!("Preset: {}", rules.preset_name().unwrap_or("Custom"))
+}
+
+fn handle_step_buttons(
+    interaction_query: Query<(&Interaction, &StepButton), Changed<Interaction>>,
+    mut rules: ResMut<MatchRules>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            button.field.step(&mut rules, button.direction);
+        }
+    }
+}
+
+fn handle_preset_buttons(
+    interaction_query: Query<(&Interaction, &PresetButton), Changed<Interaction>>,
+    mut rules: ResMut<MatchRules>,
+) {
+    for (interaction, preset) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            *rules = preset.rules();
+        }
+    }
+}
+
+fn handle_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::Title);
+    }
+}
+
+fn update_values(
+    rules: Res<MatchRules>,
+    mut value_query: Query<(&mut Text, &RuleValue)>,
+    mut preset_query: Query<&mut Text, (With<PresetText>, Without<RuleValue>)>,
+) {
+    if !rules.is_changed() {
+        return;
+    }
+    for (mut text, value) in &mut value_query {
+        **text = value.0.value(&rules);
+    }
+    if let Ok(mut text) = preset_query.get_single_mut() {
+        **text = preset_label(&rules);
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use crate::{
+    combo::{GameData, CHOICES},
+    config::{BORDER_RADIUS, BUTTON_BORDER, CHOICE_BUTTON_SIZE, NORMAL_BUTTON},
+    events::{SelectActionEvent, SelectElementEvent},
+    globals::UiAssets,
+    helper::{despawn, handle_buttons},
+    schedule::GameSet,
+    settings::GameSettings,
+    state::{GameState, UiState},
+    types::{Action, Choice, Element, Player},
+};
+
+// Mouse and touch alternative to the keys, shown under each human player
+#[derive(Component, Debug)]
+struct ChoiceButtons;
+
+#[derive(Component, Debug)]
+struct ChoiceButton {
+    player: Player,
+    element: Element,
+    action: Action,
+}
+
+pub struct ChoiceButtonsPlugin;
+
+impl Plugin for ChoiceButtonsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::Countdown), setup.in_set(GameSet::Ui));
+        app.add_systems(
+            Update,
+            (handle_buttons, handle_choice_buttons)
+                .in_set(GameSet::Ui)
+                .run_if(in_state(UiState::Countdown)),
+        );
+        app.add_systems(OnExit(UiState::Countdown), despawn::<ChoiceButtons>);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    current_state: Res<State<GameState>>,
+    ui_assets: Res<UiAssets>,
+) {
+    let selecting_actions = *current_state.get() == GameState::SelectAction;
+    let mut players = vec![];
+    for player in [Player::One, Player::Two] {
+        if settings.game_mode.human(player) {
+            players.push(player);
+        }
+    }
+
+    for player in players {
+        let (left, right) = match player {
+            Player::One => (Val::Px(75.0), Val::Auto),
+            Player::Two => (Val::Auto, Val::Px(75.0)),
+        };
+        commands
+            .spawn((
+                ChoiceButtons,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left,
+                    right,
+                    bottom: Val::Px(20.0),
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                for (element, action) in CHOICES.into_iter().take(settings.variant.choice_count()) {
+                    let choice = if selecting_actions {
+                        Choice::Action(action)
+                    } else {
+                        Choice::Element(element)
+                    };
+                    parent
+                        .spawn((
+                            ChoiceButton {
+                                player,
+                                element,
+                                action,
+                            },
+                            Button,
+                            Node {
+                                width: Val::Px(CHOICE_BUTTON_SIZE),
+                                height: Val::Px(CHOICE_BUTTON_SIZE),
+                                border: BUTTON_BORDER,
+                                padding: UiRect::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BorderColor(Color::BLACK),
+                            BorderRadius::all(BORDER_RADIUS),
+                            BackgroundColor(NORMAL_BUTTON),
+                        ))
+                        .with_child((
+                            ImageNode::new(ui_assets.get_icon(choice)),
+                            Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                        ));
+                }
+            });
+    }
+}
+
+fn handle_choice_buttons(
+    interaction_query: Query<(&Interaction, &ChoiceButton), Changed<Interaction>>,
+    current_state: Res<State<GameState>>,
+    mut game_data: ResMut<GameData>,
+    mut element_writer: EventWriter<SelectElementEvent>,
+    mut action_writer: EventWriter<SelectActionEvent>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let player_data = game_data.player_mut(button.player);
+        // Tapping the current pick again locks it in
+        match current_state.get() {
+            GameState::SelectElement => {
+                let choice = Choice::Element(button.element);
+                if player_data.choice_selection.element == choice {
+                    player_data.lock(choice);
+                }
+                player_data.select_element(button.player, choice, &mut element_writer);
+            }
+            GameState::SelectAction => {
+                let choice = Choice::Action(button.action);
+                if player_data.choice_selection.action == choice {
+                    player_data.lock(choice);
+                }
+                player_data.select_action(button.player, choice, &mut action_writer);
+            }
+            _ => (),
+        }
+    }
+}
@@ -1,15 +1,20 @@
 use bevy::prelude::*;
 
 use crate::{
-    config::{CONTROLS_HEIGHT, CONTROLS_WIDTH},
+    bindings::{pad_label, Bindings, PlayerBindings, CONFIRM},
+    combo::{GameData, CHOICES},
+    config::{CHOICE_BUTTON_SIZE, SIZE_S},
+    gamepad::PadAssignments,
     globals::UiAssets,
     helper::despawn,
+    rules::Ruleset,
     schedule::GameSet,
-    settings::{GameMode, GameSettings},
+    settings::{GameSettings, GameVariant},
     state::{GameState, UiState},
+    types::{Choice, Player},
 };
 
-const CONTROLS_SIZE_MULTIPLIER: f32 = 0.75;
+const ICON_SIZE: f32 = 40.0;
 
 #[derive(Component, Debug)]
 struct ControlsPopup;
@@ -28,26 +33,12 @@ fn setup(
     settings: Res<GameSettings>,
     current_state: Res<State<GameState>>,
     ui_assets: Res<UiAssets>,
+    game_data: Res<GameData>,
+    bindings: Res<Bindings>,
+    pads: Res<PadAssignments>,
 ) {
-    let state = current_state.get();
-    let (chart, p1_controls, p2_controls) = match state {
-        GameState::SelectElement => (
-            ui_assets.chart_elements.clone(),
-            ui_assets.controls_elements_p1.clone(),
-            ui_assets.controls_elements_p2.clone(),
-        ),
-        GameState::SelectAction => (
-            ui_assets.chart_actions.clone(),
-            ui_assets.controls_actions_p1.clone(),
-            ui_assets.controls_actions_p2.clone(),
-        ),
-        _ => (
-            ui_assets.chart_combos.clone(),
-            ui_assets.controls_elements_p1.clone(),
-            ui_assets.controls_elements_p2.clone(),
-        ),
-    };
-
+    let selecting_actions = *current_state.get() == GameState::SelectAction;
+    let count = settings.variant.choice_count();
     commands
         .spawn((
             ControlsPopup,
@@ -60,16 +51,29 @@ fn setup(
             },
         ))
         .with_children(|parent| {
-            // Root Node for Chart
-            parent
-                .spawn((Node {
-                    width: Val::Auto,
-                    height: Val::Auto,
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },))
-                .with_child((
+            // Chart of what beats what. There is no chart image for the extended set, so build it
+            if settings.variant == GameVariant::Extended {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        spawn_chart(
+                            parent,
+                            &game_data.state.ruleset,
+                            selecting_actions,
+                            &ui_assets,
+                        )
+                    });
+            } else {
+                let chart = match current_state.get() {
+                    GameState::SelectElement => ui_assets.chart_elements.clone(),
+                    GameState::SelectAction => ui_assets.chart_actions.clone(),
+                    _ => ui_assets.chart_combos.clone(),
+                };
+                parent.spawn((
                     Node {
                         width: Val::Px(250.0),
                         height: Val::Px(200.0),
@@ -78,30 +82,147 @@ fn setup(
                     },
                     ImageNode::new(chart),
                 ));
-            // Nodes for the P1 and P2 Controls
-            parent.spawn((
-                Node {
-                    width: Val::Px(CONTROLS_WIDTH * CONTROLS_SIZE_MULTIPLIER),
-                    height: Val::Px(CONTROLS_HEIGHT * CONTROLS_SIZE_MULTIPLIER),
-                    position_type: PositionType::Absolute,
-                    bottom: Val::Px(20.0),
-                    left: Val::Px(75.0),
-                    ..default()
-                },
-                ImageNode::new(p1_controls),
-            ));
-            if settings.game_mode == GameMode::TwoPlayer {
-                parent.spawn((
-                    Node {
-                        width: Val::Px(CONTROLS_WIDTH * CONTROLS_SIZE_MULTIPLIER),
-                        height: Val::Px(CONTROLS_HEIGHT * CONTROLS_SIZE_MULTIPLIER),
-                        position_type: PositionType::Absolute,
-                        bottom: Val::Px(20.0),
+            }
+            // Nodes for the P1 and P2 Controls, above their choice buttons
+            if settings.game_mode.human(Player::One) {
+                spawn_controls(
+                    parent,
+                    &bindings.player_one,
+                    pads.pad(Player::One).is_some(),
+                    count,
+                    selecting_actions,
+                    UiRect {
+                        left: Val::Px(75.0),
+                        bottom: Val::Px(CHOICE_BUTTON_SIZE + 40.0),
+                        ..default()
+                    },
+                    &ui_assets,
+                );
+            }
+            if settings.game_mode.human(Player::Two) {
+                spawn_controls(
+                    parent,
+                    &bindings.player_two,
+                    pads.pad(Player::Two).is_some(),
+                    count,
+                    selecting_actions,
+                    UiRect {
                         right: Val::Px(75.0),
+                        bottom: Val::Px(CHOICE_BUTTON_SIZE + 40.0),
                         ..default()
                     },
-                    ImageNode::new(p2_controls),
-                ));
+                    &ui_assets,
+                );
+            }
+        });
+}
+
+fn spawn_chart(
+    parent: &mut ChildBuilder,
+    ruleset: &Ruleset,
+    selecting_actions: bool,
+    ui_assets: &UiAssets,
+) {
+    let choices: Vec<Choice> = if selecting_actions {
+        ruleset.actions().into_iter().map(Choice::Action).collect()
+    } else {
+        ruleset
+            .elements()
+            .into_iter()
+            .map(Choice::Element)
+            .collect()
+    };
+    for &choice in &choices {
+        parent
+            .spawn(Node {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(4.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                spawn_icon(parent, choice, ui_assets);
+                spawn_label(parent, "beats", ui_assets);
+                for &other in &choices {
+                    if ruleset.compare(choice, other).is_gt() {
+                        spawn_icon(parent, other, ui_assets);
+                    }
+                }
+            });
+    }
+}
+
+// Shows the player's bound keys, and their buttons too once they have a controller
+fn spawn_controls(
+    parent: &mut ChildBuilder,
+    bindings: &PlayerBindings,
+    has_pad: bool,
+    count: usize,
+    selecting_actions: bool,
+    position: UiRect,
+    ui_assets: &UiAssets,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            position_type: PositionType::Absolute,
+            left: position.left,
+            right: position.right,
+            bottom: position.bottom,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            let label = |slot: usize| {
+                if has_pad {
+                    format!(
+                        "{} / {}",
+                        bindings.keys[slot].label,
+                        pad_label(bindings.pad[slot][0])
+                    )
+                } else {
+                    bindings.keys[slot].label.clone()
+                }
+            };
+            for (slot, (element, action)) in CHOICES.into_iter().take(count).enumerate() {
+                let choice = if selecting_actions {
+                    Choice::Action(action)
+                } else {
+                    Choice::Element(element)
+                };
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(8.0),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        spawn_label(parent, &label(slot), ui_assets);
+                        spawn_icon(parent, choice, ui_assets);
+                    });
             }
+            spawn_label(parent, &format!("{}: Lock In", label(CONFIRM)), ui_assets);
         });
 }
+
+fn spawn_icon(parent: &mut ChildBuilder, choice: Choice, ui_assets: &UiAssets) {
+    parent.spawn((
+        ImageNode::new(ui_assets.get_icon(choice)),
+        Node {
+            width: Val::Px(ICON_SIZE),
+            height: Val::Px(ICON_SIZE),
+            ..default()
+        },
+    ));
+}
+
+fn spawn_label(parent: &mut ChildBuilder, text: &str, ui_assets: &UiAssets) {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font: ui_assets.ms_pain.clone(),
+            font_size: SIZE_S,
+            ..default()
+        },
+        TextColor::BLACK,
+    ));
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::combo::GameData;
 use crate::config::{SIZE_S, START_STATE};
 use crate::events::SelectElementEvent;
 use crate::helper::{despawn, hide, show};
@@ -7,6 +8,7 @@ use crate::schedule::GameSet;
 use crate::state::{GameState, UiState};
 
 use crate::globals::UiAssets;
+use crate::rules::Ruleset;
 use crate::types::{Choice, Player};
 
 #[derive(Component, Debug)]
@@ -105,6 +107,7 @@ fn spawn_element_popup(
         });
 }
 
+#[allow(clippy::type_complexity)]
 fn apply_effects(
     mut reader: EventReader<SelectElementEvent>,
     mut player_one_popup: Query<(&mut Visibility, &Children), With<PlayerOneElement>>,
@@ -115,6 +118,7 @@ fn apply_effects(
     mut image_query: Query<&mut ImageNode>,
     mut text_query: Query<&mut Text>,
     ui_assets: Res<UiAssets>,
+    game_data: Res<GameData>,
 ) {
     for event in reader.read() {
         match event.player {
@@ -127,6 +131,7 @@ fn apply_effects(
                         &mut text_query,
                         &mut image_query,
                         &ui_assets,
+                        &game_data.state.ruleset,
                     );
                 }
             }
@@ -139,6 +144,7 @@ fn apply_effects(
                         &mut text_query,
                         &mut image_query,
                         &ui_assets,
+                        &game_data.state.ruleset,
                     );
                 }
             }
@@ -153,6 +159,7 @@ fn update_element(
     text_query: &mut Query<&mut Text>,
     image_query: &mut Query<&mut ImageNode>,
     ui_assets: &Res<UiAssets>,
+    ruleset: &Ruleset,
 ) {
     *visibility = Visibility::Visible;
     for &child in children {
@@ -160,7 +167,7 @@ fn update_element(
             **text = " attacks do double damage!".to_string();
         }
         if let Ok(mut image) = image_query.get_mut(child) {
-            *image = ImageNode::new(ui_assets.get_icon(Choice::get_complement(element)));
+            *image = ImageNode::new(ui_assets.get_icon(ruleset.get_complement(*element)));
         }
     }
 }
@@ -1,9 +1,10 @@
 use bevy::prelude::*;
 
 use crate::combo::GameData;
-use crate::config::{MAX_HEALTH, START_STATE};
+use crate::config::START_STATE;
 use crate::events::ApplyEffectsEvent;
 use crate::helper::{despawn, hide, show};
+use crate::rules::MatchRules;
 use crate::schedule::GameSet;
 use crate::state::{GameState, UiState};
 
@@ -36,7 +37,12 @@ impl Plugin for HealthPopupPlugin {
     }
 }
 
-fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
+// Hearts shrink to fit when there are too many for half the screen
+const HEART_SIZE: f32 = 75.0;
+const HEART_ROW_WIDTH: f32 = 600.0;
+
+fn setup(mut commands: Commands, ui_assets: Res<UiAssets>, rules: Res<MatchRules>) {
+    let health = rules.starting_health;
     commands
         .spawn((
             Name::new("Health Popup"),
@@ -55,6 +61,7 @@ fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
                 PlayerOneHealth,
                 JustifyContent::Start,
                 FlexDirection::Row,
+                health,
                 &ui_assets,
             );
             spawn_health(
@@ -63,6 +70,7 @@ fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
                 PlayerTwoHealth,
                 JustifyContent::End,
                 FlexDirection::RowReverse,
+                health,
                 &ui_assets,
             )
         });
@@ -74,8 +82,10 @@ fn spawn_health(
     player: impl Component,
     justify_content: JustifyContent,
     flex_direction: FlexDirection,
+    health: i32,
     ui_assets: &Res<UiAssets>,
 ) {
+    let size = HEART_SIZE.min(HEART_ROW_WIDTH / health.max(1) as f32);
     parent
         .spawn((
             name,
@@ -88,13 +98,13 @@ fn spawn_health(
             },
         ))
         .with_children(|parent| {
-            for _ in 0..MAX_HEALTH {
+            for _ in 0..health {
                 parent.spawn((
                     HealthPopupItem,
                     ImageNode::new(ui_assets.heart_full.clone()),
                     Node {
-                        width: Val::Px(75.0),
-                        height: Val::Px(75.0),
+                        width: Val::Px(size),
+                        height: Val::Px(size),
                         ..default()
                     },
                 ));
@@ -114,7 +124,7 @@ fn apply_effects(
         if let Ok(player_one_children) = player_one_health_popup.get_single() {
             apply_health_effects(
                 player_one_children,
-                game_data.player_one.health,
+                game_data.state.player_one.health,
                 &mut health_popup_items,
                 &ui_assets,
             );
@@ -123,7 +133,7 @@ fn apply_effects(
         if let Ok(player_two_children) = player_two_health_popup.get_single() {
             apply_health_effects(
                 player_two_children,
-                game_data.player_two.health,
+                game_data.state.player_two.health,
                 &mut health_popup_items,
                 &ui_assets,
             );
@@ -137,14 +147,12 @@ fn apply_health_effects(
     health_popup_items: &mut Query<&mut ImageNode, With<HealthPopupItem>>,
     ui_assets: &UiAssets,
 ) {
-    for i in 0..MAX_HEALTH {
-        if let Some(&child) = children.get(i as usize) {
-            if let Ok(mut health_item) = health_popup_items.get_mut(child) {
-                if health < i + 1 {
-                    health_item.image = ui_assets.heart_broken.clone();
-                } else {
-                    health_item.image = ui_assets.heart_full.clone();
-                }
+    for (i, &child) in children.iter().enumerate() {
+        if let Ok(mut health_item) = health_popup_items.get_mut(child) {
+            if health < i as i32 + 1 {
+                health_item.image = ui_assets.heart_broken.clone();
+            } else {
+                health_item.image = ui_assets.heart_full.clone();
             }
         }
     }
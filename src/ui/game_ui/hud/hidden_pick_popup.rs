@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::combo::GameData;
+use crate::config::START_STATE;
+use crate::helper::despawn;
+use crate::schedule::GameSet;
+use crate::settings::GameSettings;
+use crate::state::GameState;
+use crate::types::Player;
+
+use crate::globals::UiAssets;
+
+const ICON_SIZE: Val = Val::Px(80.0);
+
+#[derive(Component, Debug)]
+struct HiddenPickPopup;
+
+#[derive(Component, Debug)]
+struct HiddenPickIcon(Player);
+
+pub struct HiddenPickPopupPlugin;
+
+impl Plugin for HiddenPickPopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(START_STATE), setup);
+        app.add_systems(Update, update_icons.in_set(GameSet::Ui));
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            despawn::<HiddenPickPopup>.in_set(GameSet::Ui),
+        );
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<GameSettings>, ui_assets: Res<UiAssets>) {
+    if !settings.blind_picks {
+        return;
+    }
+    commands
+        .spawn((
+            Name::new("Hidden Pick Popup"),
+            HiddenPickPopup,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::top(Val::Px(220.0)),
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for player in [Player::One, Player::Two] {
+                parent
+                    .spawn(Node {
+                        width: Val::Percent(50.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    })
+                    .with_child((
+                        HiddenPickIcon(player),
+                        ImageNode::new(ui_assets.unknown.clone()),
+                        Node {
+                            width: ICON_SIZE,
+                            height: ICON_SIZE,
+                            ..default()
+                        },
+                        Visibility::Hidden,
+                    ));
+            }
+        });
+}
+
+// Only shows that a pick was made, never what it was
+fn update_icons(game_data: Res<GameData>, mut query: Query<(&mut Visibility, &HiddenPickIcon)>) {
+    for (mut visibility, icon) in &mut query {
+        *visibility = if game_data.player(icon.0).hidden_pick {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::combo::GameData;
+use crate::config::{SIZE_M, START_STATE};
+use crate::helper::{despawn, hide};
+use crate::schedule::GameSet;
+use crate::state::{GameState, UiState};
+use crate::types::Player;
+
+use crate::globals::UiAssets;
+
+const LOCKED_COLOUR: Color = Color::srgb(0.1, 0.5, 0.1);
+
+#[derive(Component, Debug)]
+struct LockPopup;
+
+#[derive(Component, Debug)]
+struct LockedText(Player);
+
+pub struct LockPopupPlugin;
+
+impl Plugin for LockPopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(START_STATE), setup);
+        app.add_systems(
+            Update,
+            update_badges
+                .in_set(GameSet::Ui)
+                .run_if(in_state(UiState::Countdown)),
+        );
+        app.add_systems(OnExit(UiState::Countdown), hide::<LockedText>);
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            despawn::<LockPopup>.in_set(GameSet::Ui),
+        );
+    }
+}
+
+fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
+    commands
+        .spawn((
+            Name::new("Lock Popup"),
+            LockPopup,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::top(Val::Px(150.0)),
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for player in [Player::One, Player::Two] {
+                parent
+                    .spawn(Node {
+                        width: Val::Percent(50.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    })
+                    .with_child((
+                        LockedText(player),
+                        Text::new("LOCKED"),
+                        TextFont {
+                            font: ui_assets.ms_pain.clone(),
+                            font_size: SIZE_M,
+                            ..default()
+                        },
+                        TextColor(LOCKED_COLOUR),
+                        Visibility::Hidden,
+                    ));
+            }
+        });
+}
+
+// Shares the TIMED OUT spot, which is only shown once the countdown is over
+fn update_badges(game_data: Res<GameData>, mut query: Query<(&mut Visibility, &LockedText)>) {
+    for (mut visibility, text) in &mut query {
+        *visibility = if game_data.player(text.0).locked {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
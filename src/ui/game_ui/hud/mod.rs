@@ -3,13 +3,23 @@ use bevy::{
     prelude::*,
 };
 
+mod choice_buttons;
 mod controls_popup;
 mod element_popup;
 mod health_popup;
+mod hidden_pick_popup;
+mod lock_popup;
+mod round_popup;
+mod timeout_popup;
 
+use choice_buttons::ChoiceButtonsPlugin;
 use controls_popup::ControlsPopupPlugin;
 use element_popup::ElementPopupPlugin;
 use health_popup::HealthPopupPlugin;
+use hidden_pick_popup::HiddenPickPopupPlugin;
+use lock_popup::LockPopupPlugin;
+use round_popup::RoundPopupPlugin;
+use timeout_popup::TimeoutPopupPlugin;
 
 use crate::{
     config::{BGM_VOLUME, START_STATE},
@@ -29,6 +39,11 @@ impl Plugin for HudPlugin {
         app.add_plugins(HealthPopupPlugin);
         app.add_plugins(ElementPopupPlugin);
         app.add_plugins(ControlsPopupPlugin);
+        app.add_plugins(ChoiceButtonsPlugin);
+        app.add_plugins(RoundPopupPlugin);
+        app.add_plugins(TimeoutPopupPlugin);
+        app.add_plugins(HiddenPickPopupPlugin);
+        app.add_plugins(LockPopupPlugin);
         app.add_systems(OnEnter(START_STATE), start_audio.in_set(GameSet::Flow));
         app.add_systems(
             OnEnter(GameState::GameOver),
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::combo::GameData;
+use crate::config::{SIZE_M, SIZE_S, START_STATE};
+use crate::events::ApplyEffectsEvent;
+use crate::helper::{despawn, hide, show};
+use crate::schedule::GameSet;
+use crate::settings::{GameSettings, SetLength};
+use crate::state::{GameState, UiState};
+
+use crate::globals::UiAssets;
+
+#[derive(Component, Debug)]
+struct RoundPopup;
+
+#[derive(Component, Debug)]
+struct RoundText;
+
+#[derive(Component, Debug)]
+struct RoundWinsText;
+
+pub struct RoundPopupPlugin;
+
+impl Plugin for RoundPopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(START_STATE), setup);
+        app.add_systems(Update, apply_effects.in_set(GameSet::Ui));
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            despawn::<RoundPopup>.in_set(GameSet::Ui),
+        );
+        app.add_systems(OnEnter(UiState::Title), hide::<RoundPopup>);
+        app.add_systems(OnExit(UiState::Title), show::<RoundPopup>);
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<GameSettings>, ui_assets: Res<UiAssets>) {
+    // Single rounds have nothing to tally
+    if settings.set_length == SetLength::Single {
+        return;
+    }
+    commands
+        .spawn((
+            Name::new("Round Popup"),
+            RoundPopup,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::top(Val::Px(10.0)),
+                ..default()
+            },
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                RoundText,
+                Text::new("Round 1"),
+                TextFont {
+                    font: ui_assets.ms_pain.clone(),
+                    font_size: SIZE_S,
+                    ..default()
+                },
+                TextColor::BLACK,
+            ));
+            parent.spawn((
+                RoundWinsText,
+                Text::new("0 - 0"),
+                TextFont {
+                    font: ui_assets.ms_pain.clone(),
+                    font_size: SIZE_M,
+                    ..default()
+                },
+                TextColor::BLACK,
+            ));
+        });
+}
+
+fn apply_effects(
+    mut reader: EventReader<ApplyEffectsEvent>,
+    mut round_text: Query<&mut Text, With<RoundText>>,
+    mut round_wins_text: Query<&mut Text, (With<RoundWinsText>, Without<RoundText>)>,
+    game_data: Res<GameData>,
+) {
+    for _ in reader.read() {
+        if let Ok(mut text) = round_text.get_single_mut() {
+            **text = format!("Round {}", game_data.state.round);
+        }
+        if let Ok(mut text) = round_wins_text.get_single_mut() {
+            **text = format!(
+                "{} - {}",
+                game_data.state.player_one.round_wins, game_data.state.player_two.round_wins
+            );
+        }
+    }
+}
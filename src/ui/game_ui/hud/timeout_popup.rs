@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use crate::config::{SIZE_M, START_STATE};
+use crate::events::{ForfeitEvent, TimeoutEvent};
+use crate::helper::{despawn, hide};
+use crate::schedule::GameSet;
+use crate::state::{GameState, UiState};
+use crate::types::Player;
+
+use crate::globals::UiAssets;
+
+const TIMEOUT_COLOUR: Color = Color::srgb(0.8, 0.1, 0.1);
+
+#[derive(Component, Debug)]
+struct TimeoutPopup;
+
+#[derive(Component, Debug)]
+struct TimeoutText(Player);
+
+pub struct TimeoutPopupPlugin;
+
+impl Plugin for TimeoutPopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(START_STATE), setup);
+        app.add_systems(Update, apply_effects.in_set(GameSet::Ui));
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            despawn::<TimeoutPopup>.in_set(GameSet::Ui),
+        );
+        // Stay up through the reveal, until the next pick starts
+        app.add_systems(OnEnter(UiState::Countdown), hide::<TimeoutText>);
+    }
+}
+
+fn setup(mut commands: Commands, ui_assets: Res<UiAssets>) {
+    commands
+        .spawn((
+            Name::new("Timeout Popup"),
+            TimeoutPopup,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::top(Val::Px(150.0)),
+                align_items: AlignItems::FlexStart,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for player in [Player::One, Player::Two] {
+                parent
+                    .spawn(Node {
+                        width: Val::Percent(50.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    })
+                    .with_child((
+                        TimeoutText(player),
+                        Text::new("TIMED OUT"),
+                        TextFont {
+                            font: ui_assets.ms_pain.clone(),
+                            font_size: SIZE_M,
+                            ..default()
+                        },
+                        TextColor(TIMEOUT_COLOUR),
+                        Visibility::Hidden,
+                    ));
+            }
+        });
+}
+
+fn apply_effects(
+    mut timeout_reader: EventReader<TimeoutEvent>,
+    mut forfeit_reader: EventReader<ForfeitEvent>,
+    mut query: Query<(&mut Visibility, &mut Text, &TimeoutText)>,
+) {
+    let timeouts = timeout_reader
+        .read()
+        .map(|event| (event.player, "TIMED OUT"));
+    let forfeits = forfeit_reader.read().map(|event| (event.player, "FORFEIT"));
+    for (player, label) in timeouts.chain(forfeits) {
+        for (mut visibility, mut text, timeout) in &mut query {
+            if timeout.0 == player {
+                *visibility = Visibility::Visible;
+                **text = label.to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::Instant;
+
+use bevy::prelude::*;
+
+use crate::config::{SIZE_M, SIZE_XL};
+use crate::globals::UiAssets;
+use crate::helper::{despawn, handle_buttons, spawn_button};
+use crate::online::lan::{host_name, LanBrowser, LanGame, LanHost, LAN_ROOM};
+use crate::online::{OnlineConfig, OnlineSession};
+use crate::rules::MatchRules;
+use crate::schedule::GameSet;
+use crate::settings::GameSettings;
+use crate::state::GameState;
+
+const SEARCHING: &str = "Looking for games on the local network...";
+
+#[derive(Component, Debug)]
+struct LanGamesMenu;
+
+#[derive(Component, Debug)]
+struct HostButton;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+// Holds a button for every game found
+#[derive(Component, Debug)]
+struct GameList;
+
+#[derive(Component, Debug)]
+struct JoinButton(LanGame);
+
+#[derive(Component, Debug)]
+struct MessageText;
+
+pub struct LanGamesPlugin;
+
+impl Plugin for LanGamesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::LanGames),
+            spawn_lan_games.in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            Update,
+            (
+                handle_buttons,
+                update_games,
+                handle_host_button,
+                handle_join_buttons,
+                handle_back_button,
+            )
+                .in_set(GameSet::Ui)
+                .run_if(in_state(GameState::LanGames)),
+        );
+        app.add_systems(
+            OnExit(GameState::LanGames),
+            (despawn::<LanGamesMenu>, close_browser),
+        );
+    }
+}
+
+fn spawn_lan_games(mut commands: Commands, ui_assets: Res<UiAssets>) {
+    let browser = LanBrowser::open();
+    let message = browser.error.clone().unwrap_or(SEARCHING.to_string());
+    commands.insert_resource(browser);
+
+    let text_font = |font_size| TextFont {
+        font: ui_assets.ms_pain.clone(),
+        font_size,
+        ..default()
+    };
+    // Spawn the Root Node
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            LanGamesMenu,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn((Text::new("LAN Games"), text_font(SIZE_XL), TextColor::BLACK));
+            spawn_button(parent, HostButton, "Host Game", &ui_assets);
+            parent.spawn((
+                GameList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                MessageText,
+                Text::new(message),
+                text_font(SIZE_M),
+                TextColor::BLACK,
+            ));
+            spawn_button(parent, BackButton, "Back", &ui_assets);
+        });
+}
+
+fn update_games(
+    mut commands: Commands,
+    mut browser: ResMut<LanBrowser>,
+    list_query: Query<Entity, With<GameList>>,
+    ui_assets: Res<UiAssets>,
+) {
+    if !browser.poll(Instant::now()) {
+        return;
+    }
+    let Ok(list) = list_query.get_single() else {
+        return;
+    };
+    commands
+        .entity(list)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for game in browser.games() {
+                let label = format!("Join {}'s game", game.name);
+                spawn_button(parent, JoinButton(game.clone()), &label, &ui_assets);
+            }
+        });
+}
+
+fn handle_host_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<HostButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    mut message: Query<&mut Text, With<MessageText>>,
+    settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        match LanHost::start(host_name()) {
+            Ok(host) => {
+                // The host plays through its own relay, so its settings are used for the match
+                let config = OnlineConfig {
+                    server: host.server(),
+                    room: LAN_ROOM.to_string(),
+                };
+                commands.insert_resource(OnlineSession::connect(&config, &settings, &rules));
+                commands.insert_resource(host);
+                game_flow.set(GameState::OnlineLobby);
+            }
+            Err(e) => {
+                if let Ok(mut text) = message.get_single_mut() {
+                    **text = format!("Can't host a game: {}", e);
+                }
+            }
+        }
+    }
+}
+
+fn handle_join_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &JoinButton), Changed<Interaction>>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let config = OnlineConfig {
+            server: button.0.server.clone(),
+            room: LAN_ROOM.to_string(),
+        };
+        commands.insert_resource(OnlineSession::connect(&config, &settings, &rules));
+        game_flow.set(GameState::OnlineLobby);
+    }
+}
+
+fn handle_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::Title);
+    }
+}
+
+// Frees the port, so another game on this machine can listen
+fn close_browser(mut commands: Commands) {
+    commands.remove_resource::<LanBrowser>();
+}
@@ -1,12 +1,15 @@
-use crate::config::{
-    BGM_VOLUME, BORDER_RADIUS, BUTTON_BORDER, BUTTON_HEIGHT, BUTTON_WIDTH, NORMAL_BUTTON, SIZE_M,
-    START_STATE,
-};
+use crate::config::{BGM_VOLUME, MENU_GRID_WIDTH, SIZE_S, START_STATE};
+use crate::gamepad::PadAssignments;
 use crate::globals::{AudioAssets, UiAssets};
-use crate::helper::{despawn, handle_buttons};
+use crate::helper::{despawn, handle_buttons, spawn_button};
+use crate::online::{OnlineConfig, OnlineSession};
+use crate::opponents::{OpponentHandles, PersonalityAsset};
+use crate::replay::{start_replay, MatchRecorder};
+use crate::rules::MatchRules;
 use crate::schedule::GameSet;
-use crate::settings::{GameMode, GameSettings};
+use crate::settings::{BotDifficulty, GameMode, GameSettings, GameVariant};
 use crate::state::GameState;
+use crate::types::Player;
 use bevy::audio::{PlaybackMode, Volume};
 use bevy::prelude::*;
 
@@ -16,9 +19,64 @@ pub struct MainMenu;
 #[derive(Component, Debug)]
 pub struct SinglePlayerButton;
 
+#[derive(Component, Debug)]
+pub struct DifficultyRow;
+
+#[derive(Component, Debug)]
+pub struct DifficultyButton(BotDifficulty);
+
+// Holds a button for every named opponent that has loaded
+#[derive(Component, Debug)]
+pub struct OpponentRow;
+
+// Plays against a named opponent
+#[derive(Component, Debug)]
+pub struct OpponentButton(String);
+
 #[derive(Component, Debug)]
 pub struct TwoPlayerButton;
 
+#[derive(Component, Debug)]
+pub struct OnlineButton;
+
+#[derive(Component, Debug)]
+pub struct LanGamesButton;
+
+#[derive(Component, Debug)]
+pub struct WatchReplayButton;
+
+#[derive(Component, Debug)]
+pub struct VariantButton;
+
+#[derive(Component, Debug)]
+pub struct SetLengthButton;
+
+#[derive(Component, Debug)]
+pub struct CustomMatchButton;
+
+#[derive(Component, Debug)]
+pub struct ControlsButton;
+
+#[derive(Component, Debug)]
+pub struct BlindPicksButton;
+
+#[derive(Component, Debug)]
+pub struct WatchBotsButton;
+
+#[derive(Component, Debug)]
+pub struct WatchBotsRow;
+
+// Cycles the bot playing for a side in CPU vs CPU
+#[derive(Component, Debug)]
+pub struct BotButton(Player);
+
+#[derive(Component, Debug)]
+pub struct StartBotsButton;
+
+// Shows which sides have a controller
+#[derive(Component, Debug)]
+pub struct JoinText;
+
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
@@ -32,7 +90,22 @@ impl Plugin for MainMenuPlugin {
             (
                 handle_buttons,
                 handle_single_player_button,
+                handle_difficulty_button,
+                handle_opponent_button,
+                update_opponent_buttons,
                 handle_two_player_button,
+                handle_online_button,
+                handle_lan_games_button,
+                handle_watch_replay_button,
+                handle_variant_button,
+                handle_set_length_button,
+                handle_custom_match_button,
+                handle_controls_button,
+                handle_blind_picks_button,
+                handle_watch_bots_button,
+                handle_bot_buttons,
+                handle_start_bots_button,
+                update_join_text,
             )
                 .in_set(GameSet::Ui)
                 .run_if(in_state(GameState::Title)),
@@ -41,10 +114,16 @@ impl Plugin for MainMenuPlugin {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_main_menu(
     mut commands: Commands,
     ui_assets: Res<UiAssets>,
     audio_assets: Res<AudioAssets>,
+    recorder: Res<MatchRecorder>,
+    settings: Res<GameSettings>,
+    opponents: Res<OpponentHandles>,
+    personalities: Res<Assets<PersonalityAsset>>,
+    pads: Res<PadAssignments>,
 ) {
     // Spawn the Root Node
     commands
@@ -83,86 +162,234 @@ pub fn spawn_main_menu(
                     parent.spawn((
                         ImageNode::new(ui_assets.logo.clone()),
                         Node {
-                            width: Val::Px(512.0),
-                            height: Val::Px(256.0),
+                            width: Val::Px(384.0),
+                            height: Val::Px(192.0),
                             ..default()
                         },
                     ));
                 });
-            // Single Player Button
+            // Top-level Buttons, wrapped into a grid so the menu fits a 720p window
             parent
-                .spawn((
-                    SinglePlayerButton,
-                    Button,
-                    Node {
-                        width: BUTTON_WIDTH,
-                        height: BUTTON_HEIGHT,
-                        border: BUTTON_BORDER,
-                        // horizontally center child text
-                        justify_content: JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BorderColor(Color::BLACK),
-                    BorderRadius::all(BORDER_RADIUS),
-                    BackgroundColor(NORMAL_BUTTON),
-                ))
-                .with_child((
-                    Text::new("1 Player"),
-                    TextFont {
-                        font: ui_assets.ms_pain.clone(),
-                        font_size: SIZE_M,
-                        ..default()
-                    },
-                    TextColor::WHITE,
-                ));
-            // Two Player Button
+                .spawn(Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    width: Val::Percent(100.),
+                    max_width: MENU_GRID_WIDTH,
+                    row_gap: Val::Px(8.),
+                    column_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    // Single Player Button
+                    spawn_button(parent, SinglePlayerButton, "1 Player", &ui_assets);
+                    // Two Player Button
+                    spawn_button(parent, TwoPlayerButton, "2 Players", &ui_assets);
+                    // Online Button
+                    spawn_button(parent, OnlineButton, "Play Online", &ui_assets);
+                    // LAN Games Button
+                    spawn_button(parent, LanGamesButton, "LAN Games", &ui_assets);
+                    // Watch CPU vs CPU Button
+                    spawn_button(parent, WatchBotsButton, "Watch CPU vs CPU", &ui_assets);
+                    // Custom Match Button
+                    spawn_button(parent, CustomMatchButton, "Custom Match", &ui_assets);
+                    // Rules Variant Button
+                    spawn_button(parent, VariantButton, settings.variant.label(), &ui_assets);
+                    // Set Length Button
+                    spawn_button(
+                        parent,
+                        SetLengthButton,
+                        settings.set_length.label(),
+                        &ui_assets,
+                    );
+                    // Blind Picks Button
+                    spawn_button(
+                        parent,
+                        BlindPicksButton,
+                        blind_picks_label(settings.blind_picks),
+                        &ui_assets,
+                    );
+                    // Controls Button
+                    spawn_button(parent, ControlsButton, "Controls", &ui_assets);
+                    // Watch Replay Button
+                    if recorder.has_replay() {
+                        spawn_button(parent, WatchReplayButton, "Watch Replay", &ui_assets);
+                    }
+                });
+            // Difficulty Buttons, shown once 1 Player is pressed
+            parent
+                .spawn((DifficultyRow, menu_row()))
+                .with_children(|parent| {
+                    for difficulty in [
+                        BotDifficulty::Easy,
+                        BotDifficulty::Normal,
+                        BotDifficulty::Hard,
+                        BotDifficulty::Adaptive,
+                        BotDifficulty::Unexploitable,
+                    ] {
+                        spawn_button(
+                            parent,
+                            DifficultyButton(difficulty),
+                            difficulty.label(),
+                            &ui_assets,
+                        );
+                    }
+                });
+            // Named Opponent Buttons, shown with the difficulties
+            parent
+                .spawn((DifficultyRow, OpponentRow, menu_row()))
+                .with_children(|parent| {
+                    for personality in opponents.loaded(&personalities) {
+                        spawn_button(
+                            parent,
+                            OpponentButton(personality.name.clone()),
+                            &personality.name,
+                            &ui_assets,
+                        );
+                    }
+                });
+            // Bot Buttons, shown once Watch CPU vs CPU is pressed
             parent
-                .spawn((
-                    TwoPlayerButton,
-                    Button,
-                    Node {
-                        width: BUTTON_WIDTH,
-                        height: BUTTON_HEIGHT,
-                        border: BUTTON_BORDER,
-                        // horizontally center child text
-                        justify_content: JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BorderColor(Color::BLACK),
-                    BorderRadius::all(BORDER_RADIUS),
-                    BackgroundColor(NORMAL_BUTTON),
-                ))
-                .with_child((
-                    Text::new("2 Players"),
-                    TextFont {
-                        font: ui_assets.ms_pain.clone(),
-                        font_size: SIZE_M,
-                        ..default()
-                    },
-                    TextColor::WHITE,
-                ));
+                .spawn((WatchBotsRow, menu_row()))
+                .with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        BotButton(Player::One),
+                        &bot_label(Player::One, settings.red_bot),
+                        &ui_assets,
+                    );
+                    spawn_button(
+                        parent,
+                        BotButton(Player::Two),
+                        &bot_label(Player::Two, settings.difficulty),
+                        &ui_assets,
+                    );
+                    spawn_button(parent, StartBotsButton, "Start", &ui_assets);
+                });
+            // Controllers
+            parent.spawn((
+                JoinText,
+                Text::new(join_label(&pads)),
+                TextFont {
+                    font: ui_assets.ms_pain.clone(),
+                    font_size: SIZE_S,
+                    ..default()
+                },
+                TextColor::BLACK,
+            ));
         });
 }
 
+// A hidden row of buttons under the grid, wrapping on narrow screens
+fn menu_row() -> Node {
+    Node {
+        display: Display::None,
+        flex_wrap: FlexWrap::Wrap,
+        justify_content: JustifyContent::Center,
+        width: Val::Percent(100.),
+        row_gap: Val::Px(8.),
+        column_gap: Val::Px(8.),
+        ..default()
+    }
+}
+
+fn join_label(pads: &PadAssignments) -> String {
+    let side = |player: Player, name: &str| match pads.pad(player) {
+        Some(_) => format!("{}: Controller", name),
+        None => format!("{}: Press a button to join", name),
+    };
+    format!(
+        "{}    {}",
+        side(Player::One, "Red"),
+        side(Player::Two, "Blue")
+    )
+}
+
+fn update_join_text(pads: Res<PadAssignments>, mut query: Query<&mut Text, With<JoinText>>) {
+    if !pads.is_changed() {
+        return;
+    }
+    for mut text in &mut query {
+        **text = join_label(&pads);
+    }
+}
+
 fn handle_single_player_button(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<SinglePlayerButton>)>,
-    mut game_flow: ResMut<NextState<GameState>>,
-    mut settings: ResMut<GameSettings>,
+    mut row_query: Query<&mut Node, With<DifficultyRow>>,
 ) {
     let Ok(interaction) = interaction_query.get_single() else {
         return;
     };
 
     if *interaction == Interaction::Pressed {
-        settings.game_mode = GameMode::SinglePlayer;
-        game_flow.set(START_STATE)
+        for mut row in &mut row_query {
+            row.display = match row.display {
+                Display::None => Display::Flex,
+                _ => Display::None,
+            };
+        }
+    }
+}
+
+fn handle_difficulty_button(
+    interaction_query: Query<(&Interaction, &DifficultyButton), Changed<Interaction>>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            settings.game_mode = GameMode::SinglePlayer;
+            settings.difficulty = button.0;
+            settings.opponent = None;
+            game_flow.set(START_STATE);
+        }
+    }
+}
+
+fn handle_opponent_button(
+    interaction_query: Query<(&Interaction, &OpponentButton), Changed<Interaction>>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            settings.game_mode = GameMode::SinglePlayer;
+            settings.opponent = Some(button.0.clone());
+            game_flow.set(START_STATE);
+        }
     }
 }
 
+// Opponents can finish loading, or be edited, while the menu is open
+fn update_opponent_buttons(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PersonalityAsset>>,
+    row_query: Query<Entity, With<OpponentRow>>,
+    opponents: Res<OpponentHandles>,
+    personalities: Res<Assets<PersonalityAsset>>,
+    ui_assets: Res<UiAssets>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    let Ok(row) = row_query.get_single() else {
+        return;
+    };
+    commands
+        .entity(row)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for personality in opponents.loaded(&personalities) {
+                spawn_button(
+                    parent,
+                    OpponentButton(personality.name.clone()),
+                    &personality.name,
+                    &ui_assets,
+                );
+            }
+        });
+}
+
 fn handle_two_player_button(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<TwoPlayerButton>)>,
     mut game_flow: ResMut<NextState<GameState>>,
@@ -177,3 +404,223 @@ fn handle_two_player_button(
         game_flow.set(START_STATE);
     }
 }
+
+fn handle_online_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<OnlineButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    settings: Res<GameSettings>,
+    rules: Res<MatchRules>,
+    config: Res<OnlineConfig>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        commands.insert_resource(OnlineSession::connect(&config, &settings, &rules));
+        game_flow.set(GameState::OnlineLobby);
+    }
+}
+
+fn handle_lan_games_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<LanGamesButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::LanGames);
+    }
+}
+
+fn handle_watch_replay_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<WatchReplayButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    mut settings: ResMut<GameSettings>,
+    mut rules: ResMut<MatchRules>,
+    recorder: Res<MatchRecorder>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        if let Some(record) = recorder.load_replay() {
+            start_replay(&mut commands, &mut settings, &mut rules, record);
+            game_flow.set(START_STATE);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_variant_button(
+    interaction_query: Query<
+        (&Interaction, &Children),
+        (Changed<Interaction>, With<VariantButton>),
+    >,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let Ok((interaction, children)) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        settings.variant = match settings.variant {
+            GameVariant::Classic => GameVariant::Extended,
+            GameVariant::Extended => GameVariant::Classic,
+        };
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = settings.variant.label().to_string();
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_set_length_button(
+    interaction_query: Query<
+        (&Interaction, &Children),
+        (Changed<Interaction>, With<SetLengthButton>),
+    >,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let Ok((interaction, children)) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        settings.set_length = settings.set_length.next();
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = settings.set_length.label().to_string();
+            }
+        }
+    }
+}
+
+fn handle_custom_match_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<CustomMatchButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::CustomMatch);
+    }
+}
+
+fn handle_controls_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        game_flow.set(GameState::Controls);
+    }
+}
+
+fn blind_picks_label(blind_picks: bool) -> &'static str {
+    if blind_picks {
+        "Blind Picks"
+    } else {
+        "Open Picks"
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_blind_picks_button(
+    interaction_query: Query<
+        (&Interaction, &Children),
+        (Changed<Interaction>, With<BlindPicksButton>),
+    >,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let Ok((interaction, children)) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        settings.blind_picks = !settings.blind_picks;
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = blind_picks_label(settings.blind_picks).to_string();
+            }
+        }
+    }
+}
+
+fn bot_label(player: Player, difficulty: BotDifficulty) -> String {
+    match player {
+        Player::One => format!("Red: {}", difficulty.label()),
+        Player::Two => format!("Blue: {}", difficulty.label()),
+    }
+}
+
+fn handle_watch_bots_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<WatchBotsButton>)>,
+    mut row_query: Query<&mut Node, With<WatchBotsRow>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        if let Ok(mut row) = row_query.get_single_mut() {
+            row.display = match row.display {
+                Display::None => Display::Flex,
+                _ => Display::None,
+            };
+        }
+    }
+}
+
+fn handle_bot_buttons(
+    interaction_query: Query<(&Interaction, &BotButton, &Children), Changed<Interaction>>,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, button, children) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let difficulty = match button.0 {
+            Player::One => &mut settings.red_bot,
+            Player::Two => &mut settings.difficulty,
+        };
+        *difficulty = difficulty.next();
+        let label = bot_label(button.0, *difficulty);
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = label.clone();
+            }
+        }
+    }
+}
+
+fn handle_start_bots_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<StartBotsButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        settings.game_mode = GameMode::BotVsBot;
+        game_flow.set(START_STATE);
+    }
+}
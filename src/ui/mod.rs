@@ -1,15 +1,27 @@
+mod controls_menu;
+mod custom_match;
 mod game_ui;
+mod lan_games;
 mod main_menu;
+mod online_lobby;
 
 use bevy::prelude::*;
+use controls_menu::ControlsMenuPlugin;
+use custom_match::CustomMatchPlugin;
 use game_ui::GameUIPlugin;
+use lan_games::LanGamesPlugin;
 use main_menu::MainMenuPlugin;
+use online_lobby::OnlineLobbyPlugin;
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MainMenuPlugin);
+        app.add_plugins(CustomMatchPlugin);
+        app.add_plugins(ControlsMenuPlugin);
+        app.add_plugins(OnlineLobbyPlugin);
+        app.add_plugins(LanGamesPlugin);
         app.add_plugins(GameUIPlugin);
     }
 }
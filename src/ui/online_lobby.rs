@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+
+use crate::config::{SIZE_M, SIZE_S, SIZE_XL};
+use crate::globals::UiAssets;
+use crate::helper::{despawn, handle_buttons, spawn_button};
+use crate::online::lan::{LanHost, LAN_ROOM};
+use crate::online::OnlineSession;
+use crate::schedule::GameSet;
+use crate::state::GameState;
+
+#[derive(Component, Debug)]
+struct OnlineLobby;
+
+#[derive(Component, Debug)]
+struct StatusText;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+pub struct OnlineLobbyPlugin;
+
+impl Plugin for OnlineLobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::OnlineLobby),
+            spawn_online_lobby.in_set(GameSet::Ui),
+        );
+        app.add_systems(
+            Update,
+            (handle_buttons, update_status, handle_back_button)
+                .in_set(GameSet::Ui)
+                .run_if(in_state(GameState::OnlineLobby)),
+        );
+        app.add_systems(OnExit(GameState::OnlineLobby), despawn::<OnlineLobby>);
+    }
+}
+
+fn spawn_online_lobby(
+    mut commands: Commands,
+    ui_assets: Res<UiAssets>,
+    session: Option<Res<OnlineSession>>,
+    host: Option<Res<LanHost>>,
+) {
+    let text_font = |font_size| TextFont {
+        font: ui_assets.ms_pain.clone(),
+        font_size,
+        ..default()
+    };
+    let status = session
+        .as_ref()
+        .map_or(String::new(), |session| session.status.clone());
+    let location = match (&session, &host) {
+        (_, Some(host)) => format!("Hosting on the local network, port {}", host.port),
+        (Some(session), None) if session.room() == LAN_ROOM => {
+            format!("Joining {} on the local network", session.server())
+        }
+        (Some(session), None) => format!(
+            "Room {} on {}. Start with --server and --room to play elsewhere.",
+            session.room(),
+            session.server()
+        ),
+        (None, None) => String::new(),
+    };
+    // Spawn the Root Node
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            OnlineLobby,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn((
+                Text::new("Play Online"),
+                text_font(SIZE_XL),
+                TextColor::BLACK,
+            ));
+            parent.spawn((
+                StatusText,
+                Text::new(status),
+                text_font(SIZE_M),
+                TextColor::BLACK,
+            ));
+            parent.spawn((Text::new(location), text_font(SIZE_S), TextColor::BLACK));
+            spawn_button(parent, BackButton, "Back", &ui_assets);
+        });
+}
+
+fn update_status(
+    session: Option<Res<OnlineSession>>,
+    mut query: Query<&mut Text, With<StatusText>>,
+) {
+    let Some(session) = session else {
+        return;
+    };
+    if !session.is_changed() {
+        return;
+    }
+    for mut text in &mut query {
+        **text = session.status.clone();
+    }
+}
+
+fn handle_back_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut game_flow: ResMut<NextState<GameState>>,
+) {
+    let Ok(interaction) = interaction_query.get_single() else {
+        return;
+    };
+
+    if *interaction == Interaction::Pressed {
+        // Dropping the session hangs up, which frees the room, and a hosted game stops
+        commands.remove_resource::<OnlineSession>();
+        commands.remove_resource::<LanHost>();
+        game_flow.set(GameState::Title);
+    }
+}